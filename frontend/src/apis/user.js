@@ -8,6 +8,29 @@ export function login(data) {
   })
 }
 
+export function captcha() {
+  return request({
+    url: '/user/captcha',
+    method: 'post',
+  })
+}
+
+export function loginTotpEnroll(data) {
+  return request({
+    url: '/user/login/totp/enroll',
+    method: 'post',
+    data,
+  })
+}
+
+export function loginTotpVerify(data) {
+  return request({
+    url: '/user/login/totp/verify',
+    method: 'post',
+    data,
+  })
+}
+
 export function permission() {
   return request({
     url: '/user/permission',
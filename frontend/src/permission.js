@@ -3,7 +3,7 @@ import { useUserStore } from './stores/user'
 import { ElNotification } from 'element-plus'
 import NProgress from 'nprogress' // progress bar
 import 'nprogress/nprogress.css' // progress bar style
-import { getToken } from '@/utils/auth.js' // get token from cookie
+import { isLoggedIn } from '@/utils/auth.js' // 从 cookie 判断登录状态
 
 NProgress.configure({ showSpinner: false }) // NProgress Configuration
 
@@ -17,11 +17,8 @@ router.beforeEach(async (to, from, next) => {
   document.title = to.meta.title || '高大上'
 
   const userStore = useUserStore()
-  // 从 Cookies 获取用户的token
-  const token = getToken()
-
-  // 没有 token
-  if (!token) {
+  // 未登录（没有 token，cookie 会话模式下没有 CSRF cookie）
+  if (!isLoggedIn()) {
     // 白名单直接放行
     if (whiteList.includes(to.path)) {
       return next()
@@ -1,7 +1,7 @@
 import { defineStore } from 'pinia'
 import { publicRoutes, privateRoutes, resetRouter } from '@/router/index.js'
-import { getToken, setToken, removeToken } from '@/utils/auth.js'
-import { login, permission, logout } from '@/apis/user.js'
+import { getToken, setToken, removeToken, isCookieSession } from '@/utils/auth.js'
+import { login, loginTotpEnroll, loginTotpVerify, permission, logout } from '@/apis/user.js'
 
 /**
  * 判断当前用户是否有权限访问该路由
@@ -59,37 +59,67 @@ const useUserStore = defineStore('user', {
     avatar: '',
     introduction: '',
     roles: [],
+    permissions: [],
+    menus: [],
 
     routes: [],
   }),
   actions: {
+    // 需要两步验证时返回 twoFactor，否则直接保存 token
     async login(userInfo) {
       const res = await login(userInfo)
-      this.token = res.data.token
-      setToken(this.token)
+      if (res.data.twoFactor) {
+        return res.data.twoFactor
+      }
+      this.saveToken(res.data.token)
+    },
+
+    // cookie 会话模式下响应不含 token，会话由服务端写入的 cookie 维持
+    saveToken(token) {
+      this.token = token || ''
+      if (token) {
+        setToken(token)
+      }
+    },
+
+    async totpEnroll(pendingToken) {
+      const res = await loginTotpEnroll({ pendingToken })
+      return res.data
+    },
+
+    async totpVerify(pendingToken, code) {
+      const res = await loginTotpVerify({ pendingToken, code })
+      this.saveToken(res.data.token)
     },
 
     async getPermission() {
       const res = await permission()
-      const { roles, name, avatar, introduction } = res.data
+      const { roles, permissions, menus, name, avatar, introduction } = res.data
       if (!roles || roles.length <= 0) {
         reject('返回角色信息不能为空')
       }
       this.roles = roles
+      this.permissions = permissions || []
+      this.menus = menus || []
       this.name = name
       this.avatar = avatar
       this.introduction = introduction
     },
 
     async logout() {
-      // try {
-      //   await logout()
-      // } catch (error) {
-      //   console.warn(error)
-      // }
+      // HttpOnly 会话 cookie 脚本无法删除，需由服务端登出并清除
+      if (isCookieSession()) {
+        try {
+          await logout()
+        } catch (error) {
+          console.warn(error)
+        }
+      }
       this.name = ''
       this.token = null
       this.roles = []
+      this.permissions = []
+      this.menus = []
       this.routes = []
       removeToken() // 移除 Cookies 中的 token
       resetRouter() // 重置路由
@@ -1,6 +1,10 @@
 import Cookies from 'js-cookie'
 
 const TokenKey = 'Tester-Token'
+// 服务端开启 cookie 会话时，token 存放在脚本不可读的 HttpOnly cookie 中，
+// 同时下发脚本可读的 CSRF cookie，需在请求头中回传（与 [auth.cookie] 配置一致）
+const CsrfCookie = 'XSRF-TOKEN'
+export const CsrfHeader = 'X-XSRF-TOKEN'
 
 export function getToken() {
   return Cookies.get(TokenKey)
@@ -11,5 +15,20 @@ export function setToken(token) {
 }
 
 export function removeToken() {
+  Cookies.remove(CsrfCookie, { path: '/' })
   return Cookies.remove(TokenKey)
 }
+
+export function getCsrfToken() {
+  return Cookies.get(CsrfCookie)
+}
+
+// 是否已登录：token 模式看本地 token，cookie 模式看 CSRF cookie
+export function isLoggedIn() {
+  return Boolean(getToken() || getCsrfToken())
+}
+
+// cookie 模式下会话 cookie 只能由服务端清除
+export function isCookieSession() {
+  return !getToken() && Boolean(getCsrfToken())
+}
@@ -1,6 +1,6 @@
 import axios from 'axios'
 import { ElNotification } from 'element-plus'
-import { getToken } from '@/utils/auth.js'
+import { getToken, getCsrfToken, CsrfHeader } from '@/utils/auth.js'
 
 const baseURL = import.meta.env.DEV
   ? 'http://127.0.0.1:8080/api' // 开发模式：直连后端（带CORS）
@@ -10,6 +10,7 @@ const baseURL = import.meta.env.DEV
 const service = axios.create({
   baseURL, // url = base url + request url
   timeout: 5000, // request timeout
+  withCredentials: true, // cookie 会话模式下携带会话 cookie
 })
 
 // request interceptor
@@ -19,6 +20,11 @@ service.interceptors.request.use(
     if (token) {
       config.headers['token'] = token
     }
+    // cookie 会话模式：回传 CSRF token（双重提交校验）
+    const csrfToken = getCsrfToken()
+    if (csrfToken) {
+      config.headers[CsrfHeader] = csrfToken
+    }
     return config
   },
   (error) => {
@@ -36,7 +42,10 @@ service.interceptors.response.use(
         message: res.msg || 'Error',
         type: 'error',
       })
-      return Promise.reject(new Error(res.msg || 'Error'))
+      // 携带业务错误码，便于调用方区分处理（如须提交验证码）
+      const err = new Error(res.msg || 'Error')
+      err.code = res.code
+      return Promise.reject(err)
     } else {
       return res
     }
@@ -12,6 +12,50 @@ CREATE TABLE `accounts` (
 ) ENGINE=InnoDB AUTO_INCREMENT=20 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
 
 
+-- `first`.api_key definition
+
+CREATE TABLE `api_key` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `user_id` bigint NOT NULL COMMENT '所属用户ID',
+  `name` varchar(64) NOT NULL COMMENT '名称',
+  `prefix` varchar(16) NOT NULL COMMENT 'key 前缀，仅用于展示识别',
+  `key_hash` char(64) NOT NULL COMMENT 'key 的 SHA-256 摘要',
+  `scopes` json NOT NULL COMMENT '授权范围（权限码列表）',
+  `expires_at` datetime DEFAULT NULL COMMENT '过期时间，NULL 表示永不过期',
+  `last_used_at` datetime DEFAULT NULL COMMENT '最近使用时间',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `updated_at` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `key_hash_unique` (`key_hash`) COMMENT 'key摘要唯一约束',
+  KEY `user_id_index` (`user_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='机器客户端 API Key';
+
+
+-- `first`.audit_log definition
+
+CREATE TABLE `audit_log` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `trace_id` varchar(64) DEFAULT NULL COMMENT '请求 trace_id',
+  `actor_id` bigint DEFAULT NULL COMMENT '操作人ID，未登录时为空',
+  `actor_name` varchar(64) DEFAULT NULL COMMENT '操作人账号',
+  `impersonator_id` bigint DEFAULT NULL COMMENT '模拟登录的管理员ID',
+  `impersonator_name` varchar(64) DEFAULT NULL COMMENT '模拟登录的管理员账号',
+  `ip` varchar(64) DEFAULT NULL COMMENT '客户端IP',
+  `method` varchar(16) DEFAULT NULL COMMENT '请求方法',
+  `path` varchar(255) DEFAULT NULL COMMENT '请求路由',
+  `action` varchar(32) NOT NULL COMMENT '操作类型(create/update/delete/impersonate/impersonate_stop)',
+  `entity` varchar(64) NOT NULL COMMENT '实体（表名）',
+  `entity_id` varchar(64) NOT NULL COMMENT '实体主键',
+  `before_data` json DEFAULT NULL COMMENT '变更前（更新时仅含变更字段）',
+  `after_data` json DEFAULT NULL COMMENT '变更后（更新时仅含变更字段）',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`id`),
+  KEY `entity_index` (`entity`,`entity_id`),
+  KEY `actor_id_index` (`actor_id`),
+  KEY `created_at_index` (`created_at`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='审计日志';
+
+
 -- `first`.custom_parameter definition
 
 CREATE TABLE `custom_parameter` (
@@ -95,6 +139,44 @@ CREATE TABLE `endpoint1` (
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci COMMENT='接口基本信息表';
 
 
+-- `first`.password_history definition
+
+CREATE TABLE `password_history` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `user_id` bigint NOT NULL COMMENT '用户ID',
+  `password` varchar(128) NOT NULL COMMENT '历史密码(Argon2id PHC 字符串)',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`id`),
+  KEY `user_id_index` (`user_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='历史密码，用于禁止重复使用';
+
+
+-- `first`.permission definition
+
+CREATE TABLE `permission` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `code` varchar(64) NOT NULL COMMENT '权限码，如 endpoint:write',
+  `name` varchar(255) NOT NULL COMMENT '权限名称',
+  `description` text COMMENT '描述',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `updated_at` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `code_unique` (`code`) COMMENT 'code唯一约束'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='权限信息';
+
+INSERT INTO `permission` (`code`, `name`) VALUES
+  ('user:read', '查询用户'),
+  ('user:write', '维护用户'),
+  ('user:impersonate', '模拟登录'),
+  ('endpoint:read', '查询接口'),
+  ('endpoint:write', '维护接口'),
+  ('menu:read', '查询菜单'),
+  ('menu:write', '维护菜单'),
+  ('audit:read', '查询审计日志'),
+  ('task:read', '查询任务与调度'),
+  ('report:read', '查询测试报告');
+
+
 -- `first`.report definition
 
 CREATE TABLE `report` (
@@ -151,6 +233,48 @@ CREATE TABLE `repository_detail` (
 ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='存放 Git 仓库地址';
 
 
+-- `first`.`role` definition
+
+CREATE TABLE `role` (
+  `id` int NOT NULL COMMENT '角色ID，对应 user.role（0-管理员）',
+  `code` varchar(64) NOT NULL COMMENT '角色编码',
+  `name` varchar(255) NOT NULL COMMENT '角色名称',
+  `description` text COMMENT '描述',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `updated_at` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `code_unique` (`code`) COMMENT 'code唯一约束'
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='角色信息';
+
+INSERT INTO `role` (`id`, `code`, `name`) VALUES
+  (0, 'admin', '管理员'),
+  (1, 'user', '普通用户');
+
+
+-- `first`.role_menu definition
+
+CREATE TABLE `role_menu` (
+  `role_id` int NOT NULL COMMENT '角色ID',
+  `menu_id` bigint NOT NULL COMMENT '菜单ID',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`role_id`, `menu_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='角色可见菜单（管理员默认可见全部菜单）';
+
+
+-- `first`.role_permission definition
+
+CREATE TABLE `role_permission` (
+  `role_id` int NOT NULL COMMENT '角色ID',
+  `permission_id` bigint NOT NULL COMMENT '权限ID',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`role_id`, `permission_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='角色权限关联（管理员默认拥有全部权限）';
+
+-- 普通用户仅可读取菜单与接口，用户列表（user:read）留给管理员
+INSERT INTO `role_permission` (`role_id`, `permission_id`)
+SELECT 1, `id` FROM `permission` WHERE `code` IN ('endpoint:read', 'menu:read');
+
+
 -- `first`.schedule definition
 
 CREATE TABLE `schedule` (
@@ -184,11 +308,42 @@ CREATE TABLE `task` (
 CREATE TABLE `user` (
   `id` bigint NOT NULL AUTO_INCREMENT,
   `username` varchar(64) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '账号',
-  `password` varchar(128) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '密码',
+  `password` varchar(128) COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '密码(Argon2id PHC 字符串)',
   `salt` varchar(32) COLLATE utf8mb4_unicode_ci DEFAULT NULL COMMENT '加盐值',
   `role` int NOT NULL COMMENT '角色',
   `enable_flag` tinyint(1) NOT NULL DEFAULT '1' COMMENT '启用标志(0-禁用,1-启用)',
+  `must_change_password` tinyint(1) NOT NULL DEFAULT '0' COMMENT '下次登录须修改密码(0-否,1-是)',
+  `locale` varchar(16) COLLATE utf8mb4_unicode_ci DEFAULT NULL COMMENT '语言偏好(zh-CN/en-US)，为空时按 Accept-Language',
   `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
   `updated_at` timestamp NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
   PRIMARY KEY (`id`)
-) ENGINE=InnoDB AUTO_INCREMENT=2 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci COMMENT='用户信息';
\ No newline at end of file
+) ENGINE=InnoDB AUTO_INCREMENT=2 DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci COMMENT='用户信息';
+
+-- `first`.user_identity definition
+
+CREATE TABLE `user_identity` (
+  `id` bigint NOT NULL AUTO_INCREMENT,
+  `user_id` bigint NOT NULL COMMENT '用户ID',
+  `issuer` varchar(255) NOT NULL COMMENT '身份提供方（OIDC issuer）',
+  `subject` varchar(255) NOT NULL COMMENT '提供方中的用户标识（sub）',
+  `email` varchar(255) DEFAULT NULL COMMENT '邮箱',
+  `last_login_at` datetime DEFAULT NULL COMMENT '最近登录时间',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  PRIMARY KEY (`id`),
+  UNIQUE KEY `issuer_subject_unique` (`issuer`,`subject`) COMMENT '同一提供方的用户唯一',
+  KEY `user_id_index` (`user_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='外部身份绑定';
+
+
+-- `first`.user_totp definition
+
+CREATE TABLE `user_totp` (
+  `user_id` bigint NOT NULL COMMENT '用户ID',
+  `secret` varchar(64) NOT NULL COMMENT 'TOTP 密钥（Base32）',
+  `enabled` tinyint(1) NOT NULL DEFAULT '0' COMMENT '是否已启用(0-待确认,1-已启用)',
+  `recovery_codes` json NOT NULL COMMENT '恢复码的 SHA-256 摘要列表',
+  `last_used_step` bigint NOT NULL DEFAULT '0' COMMENT '最近一次使用的时间步，防止重放',
+  `created_at` datetime DEFAULT CURRENT_TIMESTAMP COMMENT '创建时间',
+  `updated_at` datetime DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT '更新时间',
+  PRIMARY KEY (`user_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_0900_ai_ci COMMENT='用户两步验证';
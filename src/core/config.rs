@@ -1,4 +1,4 @@
-use std::sync::LazyLock;
+use std::{collections::HashMap, sync::LazyLock};
 
 use serde::Deserialize;
 
@@ -10,6 +10,10 @@ pub struct AppInfo {
     pub version: String,
     pub addr: String,
     pub base_url: String,
+    /// 受信任的反向代理地址，支持单个 IP 或 CIDR；仅来自这些地址的连接才读取
+    /// `X-Forwarded-For` / `X-Real-IP`，为空时一律使用连接地址
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -43,6 +47,313 @@ pub struct Log {
     pub console_level: String,
 }
 
+/// 认证方式
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthMode {
+    /// Redis 会话（默认）
+    #[default]
+    Session,
+    /// 无状态 JWT，Redis 仅用于登出黑名单
+    Jwt,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct JwtConfig {
+    /// 签名算法：HS256 / EdDSA
+    pub algorithm: String,
+    /// HS256 密钥
+    pub secret: String,
+    /// EdDSA 私钥 PEM 文件路径
+    pub private_key: String,
+    /// EdDSA 公钥 PEM 文件路径
+    pub public_key: String,
+    pub issuer: String,
+    /// access token 有效期 秒
+    pub access_ttl: u64,
+    /// refresh token 有效期 秒
+    pub refresh_ttl: u64,
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: String::from("HS256"),
+            secret: String::new(),
+            private_key: String::new(),
+            public_key: String::new(),
+            issuer: String::from("axum_demo"),
+            access_ttl: 15 * 60,
+            refresh_ttl: 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+/// 登录防暴力破解配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LoginGuardConfig {
+    /// 同一账号在窗口内允许的失败次数
+    pub max_attempts: u32,
+    /// 同一 IP 在窗口内允许的失败次数
+    pub ip_max_attempts: u32,
+    /// 失败计数窗口 秒
+    pub window_secs: u64,
+    /// 首次锁定时长 秒，之后每次翻倍
+    pub lock_secs: u64,
+    /// 锁定时长上限 秒
+    pub max_lock_secs: u64,
+}
+
+impl Default for LoginGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: constant::MAX_LOGIN_ATTEMPTS,
+            ip_max_attempts: constant::MAX_LOGIN_ATTEMPTS_PER_IP,
+            window_secs: constant::LOGIN_FAIL_WINDOW_SECS,
+            lock_secs: constant::LOGIN_LOCK_SECS,
+            max_lock_secs: constant::LOGIN_LOCK_MAX_SECS,
+        }
+    }
+}
+
+/// 图形验证码难度
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptchaDifficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+}
+
+/// 登录图形验证码配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CaptchaConfig {
+    pub enabled: bool,
+    /// 账号或 IP 在失败计数窗口内失败达到该次数后须提交验证码，0 表示始终需要
+    pub threshold: u32,
+    /// 字符数
+    pub length: u32,
+    pub difficulty: CaptchaDifficulty,
+    /// 验证码有效期 秒
+    pub ttl: u64,
+    /// 同一 IP 每分钟最多获取验证码次数，0 表示不限制
+    pub rate_limit: u32,
+}
+
+impl Default for CaptchaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: constant::CAPTCHA_THRESHOLD,
+            length: 4,
+            difficulty: CaptchaDifficulty::Medium,
+            ttl: constant::CAPTCHA_SECS,
+            rate_limit: constant::CAPTCHA_RATE_LIMIT,
+        }
+    }
+}
+
+/// OpenID Connect 单点登录配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct OidcConfig {
+    pub enabled: bool,
+    /// 身份提供方地址，用于拼接 `/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// 身份提供方回调地址（前端回调页），需与提供方登记的一致
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    /// 作为用户名的 claim
+    pub username_claim: String,
+    /// 用于角色映射的 claim，值可以是字符串或字符串数组
+    pub role_claim: String,
+    /// claim 值 -> 角色 ID，命中多个时取 ID 最小者
+    pub role_mapping: HashMap<String, i32>,
+    /// 新建用户未命中映射时的角色，已有用户保留原角色
+    pub default_role: i32,
+    /// 首次登录时自动创建用户
+    pub auto_provision: bool,
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            issuer: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            redirect_uri: String::new(),
+            scopes: vec!["openid".into(), "profile".into(), "email".into()],
+            username_claim: String::from("preferred_username"),
+            role_claim: String::from("groups"),
+            role_mapping: HashMap::new(),
+            default_role: 1,
+            auto_provision: true,
+        }
+    }
+}
+
+/// TOTP 两步验证配置
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct TotpConfig {
+    /// 验证器 App 中显示的签发方
+    pub issuer: String,
+    /// 管理员必须启用两步验证，未启用时登录需先完成绑定
+    pub require_for_admin: bool,
+    /// 待验证令牌有效期 秒
+    pub pending_ttl: u64,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            issuer: String::from("axum_demo"),
+            require_for_admin: false,
+            pending_ttl: constant::TOTP_PENDING_SECS,
+        }
+    }
+}
+
+/// 密码策略
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_upper: bool,
+    pub require_lower: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// 不允许与最近 N 次的密码相同，0 表示不限制
+    pub history: usize,
+    /// 管理员重置密码后一次性令牌的有效期 秒
+    pub reset_ttl: u64,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 64,
+            require_upper: false,
+            require_lower: true,
+            require_digit: true,
+            require_symbol: false,
+            history: 5,
+            reset_ttl: constant::PASSWORD_RESET_SECS,
+        }
+    }
+}
+
+/// Cookie 的 SameSite 属性
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CookieSameSite {
+    #[default]
+    Strict,
+    Lax,
+    None,
+}
+
+/// Cookie 会话配置，用于本服务托管的前端
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct CookieConfig {
+    /// 开启后签发会话时写入 HttpOnly cookie，响应体不再返回 token
+    pub enabled: bool,
+    /// 会话 token 的 cookie 名
+    pub name: String,
+    /// refresh token 的 cookie 名（仅 JWT 模式）
+    pub refresh_name: String,
+    /// CSRF token 的 cookie 名，前端脚本可读
+    pub csrf_name: String,
+    /// 回传 CSRF token 的请求头
+    pub csrf_header: String,
+    /// 仅通过 HTTPS 发送，本地 HTTP 调试时可关闭
+    pub secure: bool,
+    pub same_site: CookieSameSite,
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            name: String::from("Token"),
+            refresh_name: String::from("RefreshToken"),
+            csrf_name: String::from("XSRF-TOKEN"),
+            csrf_header: String::from("X-XSRF-TOKEN"),
+            secure: true,
+            same_site: CookieSameSite::Strict,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub mode: AuthMode,
+    pub jwt: JwtConfig,
+    pub login: LoginGuardConfig,
+    pub captcha: CaptchaConfig,
+    pub oidc: OidcConfig,
+    pub totp: TotpConfig,
+    pub password: PasswordPolicyConfig,
+    pub cookie: CookieConfig,
+}
+
+/// 错误响应格式
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    /// `{code, msg, data}` 信封，HTTP 状态码始终为 200（默认）
+    #[default]
+    Envelope,
+    /// RFC 7807 `application/problem+json`，使用对应的 HTTP 状态码
+    Problem,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ResponseConfig {
+    /// 错误响应格式；为 envelope 时，请求头 `Accept: application/problem+json` 仍可单独选择 problem 格式
+    pub format: ResponseFormat,
+    /// 默认语言，请求未声明或声明的语言均不支持时使用
+    pub default_locale: String,
+}
+
+impl Default for ResponseConfig {
+    fn default() -> Self {
+        Self {
+            format: ResponseFormat::Envelope,
+            default_locale: String::from("zh-CN"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct QueryConfig {
+    /// 过滤条件最大嵌套层数，顶层条件计为第 1 层
+    pub max_depth: usize,
+    /// 过滤条件与分组的节点总数上限
+    pub max_nodes: usize,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_nodes: 50,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
     pub app: AppInfo,
@@ -50,6 +361,12 @@ pub struct AppConfig {
     pub redis: RedisConfig,
     pub jobs: JobsConfig,
     pub log: Log,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub response: ResponseConfig,
+    #[serde(default)]
+    pub query: QueryConfig,
 }
 
 impl AppConfig {
@@ -69,4 +386,4 @@ impl AppConfig {
     }
 }
 
-pub static CONFIG: LazyLock<AppConfig> = LazyLock::new(|| AppConfig::init());
+pub static CONFIG: LazyLock<AppConfig> = LazyLock::new(AppConfig::init);
@@ -15,23 +15,73 @@ pub const TRACE_HEADER: &str = "x-trace-id";
 /// HTTP / 路由 / Header / Cookie
 /// ==============================
 pub const SESSION_KEY: &str = "Session"; // session前缀
+pub const SESSION_INDEX_KEY: &str = "SessionIndex"; // 用户会话索引前缀
+pub const SESSION_SEEN_KEY: &str = "SessionSeen"; // 会话最近访问时间前缀
+pub const JWT_DENY_KEY: &str = "JwtDeny"; // JWT 登出黑名单前缀
+pub const JWT_USER_REVOKE_KEY: &str = "JwtUserRevoke"; // JWT 用户吊销时间（毫秒）前缀
+pub const JWT_USED_KEY: &str = "JwtUsed"; // 已使用的 refresh token（jti）前缀
 pub const AUTH_HEADER: &str = "Token"; // HTTP Header 名称
+pub const API_KEY_HEADER: &str = "X-Api-Key"; // API Key Header 名称
+pub const REFRESH_PATH: &str = "/user/token/refresh"; // 刷新 token 路由，refresh token cookie 仅发往此处
 // pub const CONTENT_TYPE_JSON: &str = "application/json"; // JSON Content-Type
 // pub const CONTENT_TYPE_FORM: &str = "application/x-www-form-urlencoded";
 
-// /// ==============================
-// /// 分页 / 限流 / 超时
-// /// ==============================
+/// ==============================
+/// 分页 / 限流 / 超时
+/// ==============================
 // pub const MIN_PAGE_SIZE: u32 = 1; // 默认分页大小
-// pub const MAX_PAGE_SIZE: u32 = 100; // 最大分页大小
-// pub const LOGIN_RATE_LIMIT: u32 = 5; // 每分钟允许登录次数
+pub const MAX_PAGE_SIZE: u64 = 100; // 最大分页大小
+pub const MAX_EXPAND_ROWS: u64 = 50; // 一对多展开时每行最多返回的关联数
 // pub const REQUEST_TIMEOUT_SECS: u64 = 10; // 请求超时秒数
 
 /// ==============================
 /// 安全 / 认证相关
 /// ==============================
 pub const EXPIRATION_SECS: u64 = 60 * 60; // 过期时间 秒
-// pub const MAX_LOGIN_ATTEMPTS: u32 = 5; // 最大登录失败次数
+pub const MAX_LOGIN_ATTEMPTS: u32 = 5; // 同一账号最大登录失败次数
+pub const MAX_LOGIN_ATTEMPTS_PER_IP: u32 = 20; // 同一 IP 最大登录失败次数
+pub const LOGIN_FAIL_WINDOW_SECS: u64 = 15 * 60; // 登录失败计数窗口 秒
+pub const LOGIN_LOCK_SECS: u64 = 60; // 首次锁定时长 秒，之后每次翻倍
+pub const LOGIN_LOCK_MAX_SECS: u64 = 60 * 60; // 锁定时长上限 秒
+pub const LOGIN_FAIL_KEY: &str = "LoginFail"; // 登录失败计数前缀
+pub const LOGIN_LOCK_KEY: &str = "LoginLock"; // 登录锁定前缀
+pub const LOGIN_LOCK_LEVEL_KEY: &str = "LoginLockLevel"; // 连续锁定次数前缀
+pub const CAPTCHA_KEY: &str = "Captcha"; // 图形验证码答案前缀
+pub const CAPTCHA_THRESHOLD: u32 = 3; // 失败达到该次数后登录须提交验证码
+pub const CAPTCHA_SECS: u64 = 2 * 60; // 图形验证码有效期 秒
+pub const CAPTCHA_RATE_KEY: &str = "CaptchaRate"; // 同一 IP 获取验证码计数前缀
+pub const CAPTCHA_RATE_LIMIT: u32 = 10; // 同一 IP 每分钟最多获取验证码次数
+pub const TOKEN_BYTES: usize = 32; // 会话 token 随机字节数
+pub const CSRF_TOKEN_BYTES: usize = 16; // CSRF token 随机字节数
+pub const ADMIN_ROLE: i32 = 0; // 管理员角色，默认拥有全部权限
+pub const API_KEY_PREFIX: &str = "ak_"; // API Key 前缀，便于识别与密钥扫描
+pub const API_KEY_BYTES: usize = 24; // API Key 随机字节数
+pub const API_KEY_TOUCH_SECS: i64 = 60; // API Key 最近使用时间的最小更新间隔 秒
+pub const OIDC_STATE_KEY: &str = "OidcState"; // OIDC 授权请求 state 前缀
+pub const OIDC_STATE_SECS: u64 = 10 * 60; // OIDC 授权请求有效期 秒
+pub const OIDC_BINDING_COOKIE: &str = "OidcBinding"; // 绑定授权请求与浏览器的 cookie 名
+pub const OIDC_CALLBACK_PATH: &str = "/user/oidc/callback"; // 单点登录回调路由，绑定 cookie 仅发往此处
+pub const TOTP_PENDING_KEY: &str = "TotpPending"; // 待两步验证的登录前缀
+pub const TOTP_PENDING_SECS: u64 = 5 * 60; // 待两步验证的登录有效期 秒
+pub const TOTP_RECOVERY_CODES: usize = 10; // 恢复码数量
+pub const PASSWORD_RESET_KEY: &str = "PasswordReset"; // 密码重置一次性令牌前缀
+pub const PASSWORD_RESET_USER_KEY: &str = "PasswordResetUser"; // 用户当前有效的重置令牌摘要前缀
+pub const PASSWORD_RESET_SECS: u64 = 24 * 60 * 60; // 密码重置令牌有效期 秒
+pub const OIDC_HTTP_TIMEOUT_SECS: u64 = 10; // 请求身份提供方的超时 秒
+
+/// ==============================
+/// 权限码（对应 permission.code）
+/// ==============================
+pub const PERM_USER_READ: &str = "user:read";
+pub const PERM_USER_WRITE: &str = "user:write";
+pub const PERM_USER_IMPERSONATE: &str = "user:impersonate";
+pub const PERM_ENDPOINT_READ: &str = "endpoint:read";
+pub const PERM_ENDPOINT_WRITE: &str = "endpoint:write";
+pub const PERM_MENU_READ: &str = "menu:read";
+pub const PERM_MENU_WRITE: &str = "menu:write";
+pub const PERM_AUDIT_READ: &str = "audit:read";
+pub const PERM_TASK_READ: &str = "task:read";
+pub const PERM_REPORT_READ: &str = "report:read";
 
 // /// ==============================
 // /// 文件 / 目录路径
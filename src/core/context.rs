@@ -0,0 +1,66 @@
+//! 请求上下文
+//!
+//! 通过 task-local 在同一请求的调用链中共享 trace_id、路由、操作人与语言，
+//! 供审计、错误渲染等不便逐层传参的场景读取。请求之外（定时任务等）读取时返回 None
+
+use std::future::Future;
+
+use crate::core::i18n::Locale;
+
+tokio::task_local! {
+    static REQUEST: RequestContext;
+    static ACTOR: Actor;
+    static LOCALE: Locale;
+}
+
+/// 请求信息，由 `log_middleware` 设置
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub trace_id: String,
+    pub method: String,
+    pub path: String,
+    pub ip: Option<String>,
+    /// 请求的 Accept 头声明接受 problem+json 格式的错误
+    pub accept_problem: bool,
+}
+
+/// 操作人，由 `user_middleware` 在认证通过后设置
+#[derive(Debug, Clone)]
+pub struct Actor {
+    pub user_id: i64,
+    pub username: String,
+    /// 模拟登录时的真实操作人（管理员）
+    pub impersonator: Option<(i64, String)>,
+}
+
+/// 在请求上下文中执行
+pub async fn scope_request<F: Future>(ctx: RequestContext, f: F) -> F::Output {
+    REQUEST.scope(ctx, f).await
+}
+
+/// 在操作人上下文中执行
+pub async fn scope_actor<F: Future>(actor: Actor, f: F) -> F::Output {
+    ACTOR.scope(actor, f).await
+}
+
+/// 在指定语言下执行，内层设置覆盖外层（用户偏好覆盖 `Accept-Language`）
+pub async fn scope_locale<F: Future>(locale: Locale, f: F) -> F::Output {
+    LOCALE.scope(locale, f).await
+}
+
+/// 当前请求信息
+pub fn request() -> Option<RequestContext> {
+    REQUEST.try_with(|c| c.clone()).ok()
+}
+
+/// 当前操作人
+pub fn actor() -> Option<Actor> {
+    ACTOR.try_with(|a| a.clone()).ok()
+}
+
+/// 当前请求的语言，未设置时为配置的默认语言
+pub fn locale() -> Locale {
+    LOCALE
+        .try_with(|l| *l)
+        .unwrap_or_else(|_| Locale::default_locale())
+}
@@ -0,0 +1,304 @@
+//! Cookie 会话传输
+//!
+//! 开启 `[auth.cookie]` 后，签发会话的接口把 token 写入 HttpOnly cookie 并从响应体中移除，
+//! 前端脚本无法读取 token。CSRF 采用双重提交：登录时另外下发脚本可读的 CSRF cookie，
+//! 通过 cookie 认证的非安全方法请求须在请求头中回传相同的值
+//!
+//! 单点登录的授权请求另以 HttpOnly cookie 绑定到发起登录的浏览器，与是否开启 cookie 模式无关
+
+use axum::{
+    http::{HeaderMap, Method},
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+
+use crate::{
+    Error,
+    core::{
+        config::{CONFIG, CookieConfig, CookieSameSite},
+        constant,
+    },
+    error::Resp,
+    utils::{digest, random},
+    vo::user_vo::LoginResp,
+};
+
+/// 是否开启 cookie 模式
+pub fn enabled() -> bool {
+    CONFIG.auth.cookie.enabled
+}
+
+/// 读取会话 token cookie，未开启 cookie 模式时返回 None
+pub fn session_token(headers: &HeaderMap) -> Option<String> {
+    let conf = &CONFIG.auth.cookie;
+    read(conf, headers, &conf.name)
+}
+
+/// 读取 refresh token cookie，未开启 cookie 模式时返回 None
+pub fn refresh_token(headers: &HeaderMap) -> Option<String> {
+    let conf = &CONFIG.auth.cookie;
+    read(conf, headers, &conf.refresh_name)
+}
+
+fn read(conf: &CookieConfig, headers: &HeaderMap, name: &str) -> Option<String> {
+    if !conf.enabled {
+        return None;
+    }
+    CookieJar::from_headers(headers)
+        .get(name)
+        .map(|c| c.value().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 校验双重提交的 CSRF token，GET 等安全方法不校验
+pub fn verify_csrf(method: &Method, headers: &HeaderMap) -> Result<(), Error> {
+    check_csrf(&CONFIG.auth.cookie, method, headers)
+}
+
+fn check_csrf(conf: &CookieConfig, method: &Method, headers: &HeaderMap) -> Result<(), Error> {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(());
+    }
+    let cookie = read(conf, headers, &conf.csrf_name);
+    let header = headers
+        .get(conf.csrf_header.as_str())
+        .and_then(|h| h.to_str().ok());
+    match (cookie, header) {
+        // 比较摘要，避免逐字节比较泄露匹配长度
+        (Some(cookie), Some(header))
+            if digest::sha256(cookie.as_bytes()) == digest::sha256(header.as_bytes()) =>
+        {
+            Ok(())
+        }
+        _ => Err(Error::Unauthorized("CSRF 校验失败".into())),
+    }
+}
+
+fn build(
+    conf: &CookieConfig,
+    name: &str,
+    value: String,
+    path: String,
+    http_only: bool,
+) -> Cookie<'static> {
+    let same_site = match conf.same_site {
+        CookieSameSite::Strict => SameSite::Strict,
+        CookieSameSite::Lax => SameSite::Lax,
+        CookieSameSite::None => SameSite::None,
+    };
+    Cookie::build((name.to_string(), value))
+        .path(path)
+        .http_only(http_only)
+        .secure(conf.secure)
+        .same_site(same_site)
+        .build()
+}
+
+/// 会话 cookie 只发往接口路由
+fn api_path() -> String {
+    match CONFIG.app.base_url.as_str() {
+        "" => String::from("/"),
+        base => base.to_string(),
+    }
+}
+
+fn refresh_path() -> String {
+    format!(
+        "{}{}",
+        CONFIG.app.base_url.trim_end_matches('/'),
+        constant::REFRESH_PATH
+    )
+}
+
+fn oidc_callback_path() -> String {
+    format!(
+        "{}{}",
+        CONFIG.app.base_url.trim_end_matches('/'),
+        constant::OIDC_CALLBACK_PATH
+    )
+}
+
+/// 写入单点登录授权请求的绑定值，仅发往回调接口
+pub fn set_oidc_binding(binding: String) -> CookieJar {
+    CookieJar::new().add(build(
+        &CONFIG.auth.cookie,
+        constant::OIDC_BINDING_COOKIE,
+        binding,
+        oidc_callback_path(),
+        true,
+    ))
+}
+
+/// 读取单点登录授权请求的绑定值
+pub fn oidc_binding(headers: &HeaderMap) -> Option<String> {
+    CookieJar::from_headers(headers)
+        .get(constant::OIDC_BINDING_COOKIE)
+        .map(|c| c.value().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 回调完成后清除绑定 cookie
+pub fn clear_oidc_binding() -> CookieJar {
+    CookieJar::new().remove(Cookie::build(constant::OIDC_BINDING_COOKIE).path(oidc_callback_path()))
+}
+
+/// 清除会话相关 cookie（登出时使用），未开启 cookie 模式时为空
+pub fn clear() -> CookieJar {
+    let jar = CookieJar::new();
+    if !enabled() {
+        return jar;
+    }
+    let conf = &CONFIG.auth.cookie;
+    jar.remove(Cookie::build(conf.name.clone()).path(api_path()))
+        .remove(Cookie::build(conf.csrf_name.clone()).path("/"))
+        .remove(Cookie::build(conf.refresh_name.clone()).path(refresh_path()))
+}
+
+/// 签发会话的响应：cookie 模式下 token 写入 cookie 并从响应体中移除，同时下发新的 CSRF token
+pub struct SessionResponse(pub LoginResp);
+
+impl From<LoginResp> for SessionResponse {
+    fn from(resp: LoginResp) -> Self {
+        Self(resp)
+    }
+}
+
+impl IntoResponse for SessionResponse {
+    fn into_response(self) -> Response {
+        self.respond(&CONFIG.auth.cookie)
+    }
+}
+
+impl SessionResponse {
+    fn respond(self, conf: &CookieConfig) -> Response {
+        let mut resp = self.0;
+        if !conf.enabled {
+            return Resp::from(resp).into_response();
+        }
+        let mut jar = CookieJar::new();
+        if let Some(token) = resp.token.take() {
+            jar = jar
+                .add(build(conf, &conf.name, token, api_path(), true))
+                // 前端脚本读取后放入请求头回传；路径为根，托管的页面均可读取
+                .add(build(
+                    conf,
+                    &conf.csrf_name,
+                    random::token(constant::CSRF_TOKEN_BYTES),
+                    String::from("/"),
+                    false,
+                ));
+        }
+        if let Some(refresh_token) = resp.refresh_token.take() {
+            jar = jar.add(build(
+                conf,
+                &conf.refresh_name,
+                refresh_token,
+                refresh_path(),
+                true,
+            ));
+        }
+        (jar, Resp::from(resp)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod test_cookie {
+    use super::*;
+    use axum::http::{HeaderValue, header};
+
+    fn enabled_conf() -> CookieConfig {
+        CookieConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    fn headers(cookie: &str, csrf: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_str(cookie).unwrap());
+        if let Some(csrf) = csrf {
+            headers.insert("X-XSRF-TOKEN", HeaderValue::from_str(csrf).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_verify_csrf() {
+        let conf = enabled_conf();
+        let post = Method::POST;
+        let cookie = "Token=t1; XSRF-TOKEN=abc";
+        assert!(check_csrf(&conf, &post, &headers(cookie, Some("abc"))).is_ok());
+        // 安全方法不校验
+        assert!(check_csrf(&conf, &Method::GET, &headers("Token=t1", None)).is_ok());
+
+        for headers in [
+            headers(cookie, None),
+            headers(cookie, Some("abd")),
+            headers("Token=t1", Some("abc")),
+        ] {
+            let res = check_csrf(&conf, &post, &headers);
+            assert!(matches!(res, Err(Error::Unauthorized(_))));
+        }
+        // 未开启 cookie 模式时不读取 cookie
+        let res = check_csrf(
+            &CookieConfig::default(),
+            &post,
+            &headers(cookie, Some("abc")),
+        );
+        assert!(res.is_err());
+    }
+
+    fn login_resp() -> LoginResp {
+        LoginResp {
+            user_id: 1,
+            token: Some(String::from("session-token")),
+            refresh_token: Some(String::from("refresh-token")),
+            username: String::from("admin"),
+            role: 0,
+            must_change_password: false,
+            two_factor: None,
+            impersonator: None,
+        }
+    }
+
+    async fn body(resp: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_response() {
+        // cookie 模式：token 只出现在 HttpOnly cookie 中
+        let resp = SessionResponse(login_resp()).respond(&enabled_conf());
+        let cookies: Vec<String> = resp
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|v| v.to_str().unwrap().to_string())
+            .collect();
+        let find = |name: &str| {
+            cookies
+                .iter()
+                .find(|c| c.starts_with(&format!("{}=", name)))
+                .cloned()
+                .unwrap()
+        };
+        let session = find("Token");
+        assert!(session.starts_with("Token=session-token;"));
+        assert!(session.contains("HttpOnly") && session.contains("Secure"));
+        assert!(find("RefreshToken").contains("HttpOnly"));
+        assert!(!find("XSRF-TOKEN").contains("HttpOnly"));
+        let value = body(resp).await;
+        assert_eq!(value["data"]["userId"], 1);
+        assert!(value["data"].get("token").is_none());
+        assert!(value["data"].get("refreshToken").is_none());
+
+        // 未开启时 token 仍在响应体中，不写 cookie
+        let resp = SessionResponse(login_resp()).respond(&CookieConfig::default());
+        assert!(resp.headers().get(header::SET_COOKIE).is_none());
+        let value = body(resp).await;
+        assert_eq!(value["data"]["token"], "session-token");
+    }
+}
@@ -1,90 +1,396 @@
-use std::time::Duration;
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+};
 
-use crate::{AppState, Error, core::constant, entity, utils::RedisUtil};
-use axum::{extract::FromRequestParts, http::request::Parts};
-use serde::{Deserialize, Serialize};
+use crate::{
+    AppState, Error,
+    core::{
+        config::{AuthMode, CONFIG},
+        constant, jwt,
+        session::{self, SessionMeta},
+    },
+    dao::PermissionDao,
+    entity,
+    utils::{RedisUtil, random},
+};
+use axum::{
+    Json,
+    extract::{ConnectInfo, FromRequest, FromRequestParts, Request},
+    http::{header, request::Parts},
+    middleware::{FromExtractorLayer, from_extractor_with_state},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use tracing::info;
+use validator::Validate;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UserInfo {
     pub token: String,
     pub user_db: entity::user::Model,
+    /// 角色拥有的权限码，登录时加载
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// 会话元信息（签发时间、最近访问、IP、UA）
+    #[serde(default)]
+    pub session: SessionMeta,
+    /// JWT 模式下登录时签发的 refresh token，不随会话保存
+    #[serde(skip)]
+    pub refresh_token: Option<String>,
+    /// 通过 API Key 认证时的授权范围，权限不超出该范围（管理员同样受限）
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+    /// 模拟登录时发起模拟的管理员
+    #[serde(default)]
+    pub impersonator: Option<Impersonator>,
+}
+
+/// 模拟登录的真实操作人
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Impersonator {
+    pub user_id: i64,
+    pub username: String,
+    /// 管理员自己的会话 ID，结束模拟时据此恢复
+    pub session_id: String,
 }
 
 impl UserInfo {
+    /// 根据认证方式校验 token 并获取用户信息；会话模式下同时续期
+    pub async fn authenticate(token: &str, state: &AppState) -> Result<Self, Error> {
+        match CONFIG.auth.mode {
+            AuthMode::Session => {
+                let mut user = Self::from_token(token, state).await?;
+                // 会话会在用户变更时同步，此处兜底拒绝已禁用的用户
+                if !user.is_enabled() {
+                    return Err(Error::NotLogin);
+                }
+                // 模拟会话随管理员会话一同失效（管理员登出、被禁用或会话被吊销）
+                if let Some(admin) = &user.impersonator
+                    && session::token_of(state, admin.user_id, &admin.session_id)
+                        .await?
+                        .is_none()
+                {
+                    session::revoke(state, user.user_db.id, &user.session.id).await?;
+                    return Err(Error::NotLogin);
+                }
+                user.refresh_session(state).await?;
+                Ok(user)
+            }
+            AuthMode::Jwt => {
+                let claims = jwt::verify(token, jwt::TokenType::Access, state).await?;
+                Ok(jwt::user_from_claims(token, claims))
+            }
+        }
+    }
+
     pub async fn from_token(token: &str, state: &AppState) -> Result<Self, Error> {
         // 1. 从 Redis 获取用户信息
         let redis = RedisUtil::new(state.redis.clone());
-        let value = redis.get::<String>(&token).await?.ok_or(Error::NotLogin)?;
+        let value = redis
+            .get::<String>(&session::session_key(token))
+            .await?
+            .ok_or(Error::NotLogin)?;
 
         // 2. 解析 user_info
-        let user: entity::user::Model = serde_json::from_str(&value)
-            .map_err(|e| Error::Unknown(format!("转换 user 失败:{}", e)))?;
-        Ok(Self {
-            token: token.to_string(),
-            user_db: user,
-        })
+        serde_json::from_str(&value).map_err(|e| Error::unknown("转换 user 失败").with_source(e))
     }
 
-    pub async fn login(user_db: entity::user::Model, state: &AppState) -> Result<Self, Error> {
-        // 生成随机值存放数据库
-        let salt = chrono::Local::now().timestamp();
-        // 登陆信息存放在 redis 中
-        let redis = RedisUtil::new(state.redis.clone());
-        let token = format!("{}:{}:{}", constant::SESSION_KEY, user_db.id, salt);
-        redis
-            .set_with_expire(
-                &token,
-                serde_json::to_string(&user_db).unwrap(),
-                Duration::from_secs(constant::EXPIRATION_SECS),
-            )
-            .await?;
-        info!("User login successful: {:?}", &user_db.username);
-        Ok(Self { token, user_db })
-    }
-
-    /// 刷新会话有效期
-    pub async fn refresh_session(&self, state: &AppState) -> Result<(), Error> {
-        let redis = RedisUtil::new(state.redis.clone());
-        //  刷新过期时间
-        redis
-            .set_with_expire(
-                &self.token,
-                serde_json::to_string(&self.user_db).unwrap(),
-                Duration::from_secs(constant::EXPIRATION_SECS),
-            )
-            .await?;
-        Ok(())
+    pub async fn login(
+        user_db: entity::user::Model,
+        client: &ClientInfo,
+        state: &AppState,
+    ) -> Result<Self, Error> {
+        Self::sign_in(user_db, client, state, None).await
+    }
+
+    /// 以指定用户身份签发会话，会话中同时记录发起模拟的管理员
+    pub async fn impersonate(
+        user_db: entity::user::Model,
+        impersonator: Impersonator,
+        client: &ClientInfo,
+        state: &AppState,
+    ) -> Result<Self, Error> {
+        Self::sign_in(user_db, client, state, Some(impersonator)).await
+    }
+
+    async fn sign_in(
+        user_db: entity::user::Model,
+        client: &ClientInfo,
+        state: &AppState,
+        impersonator: Option<Impersonator>,
+    ) -> Result<Self, Error> {
+        // 加载角色权限，随会话一起缓存
+        let permissions = PermissionDao::query_codes_by_role(&state.db, user_db.role).await?;
+        let mut user = Self {
+            // 生成不可猜测的随机 token
+            token: random::token(constant::TOKEN_BYTES),
+            user_db,
+            permissions,
+            session: SessionMeta::new(client),
+            refresh_token: None,
+            scopes: None,
+            impersonator,
+        };
+        match CONFIG.auth.mode {
+            AuthMode::Session => {
+                // 登陆信息存放在 redis 中，并登记到用户会话索引
+                session::create(state, &user).await?;
+            }
+            AuthMode::Jwt => {
+                let pair =
+                    jwt::issue(&user.user_db, &user.permissions, user.impersonator.as_ref())?;
+                user.token = pair.access_token;
+                user.refresh_token = Some(pair.refresh_token);
+            }
+        }
+        match &user.impersonator {
+            Some(admin) => info!(
+                "Impersonation started: {} as {:?}",
+                admin.username, &user.user_db.username
+            ),
+            None => info!("User login successful: {:?}", &user.user_db.username),
+        }
+        Ok(user)
+    }
+
+    /// 刷新会话有效期，并记录最近访问时间
+    pub async fn refresh_session(&mut self, state: &AppState) -> Result<(), Error> {
+        self.session.last_seen = chrono::Local::now().naive_local();
+        session::touch(state, self).await
     }
 
     /// 检查用户角色是否有权限
     /// 0 - 管理员 1 - 普通用户
     /// 返回 true 表示有权限，false 表示无权限
     pub fn is_admin(&self) -> bool {
-        self.user_db.role == 0
+        self.user_db.role == constant::ADMIN_ROLE
+    }
+
+    /// 用户是否处于启用状态
+    pub fn is_enabled(&self) -> bool {
+        self.user_db.enable_flag == 1
+    }
+
+    /// 是否通过 API Key 认证
+    pub fn is_api_key(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    /// 是否为管理员模拟登录的会话
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator.is_some()
+    }
+
+    /// 是否须先修改密码
+    pub fn must_change_password(&self) -> bool {
+        self.user_db.must_change_password == 1
+    }
+
+    /// 检查是否拥有指定权限码，管理员拥有全部权限；API Key 还需在授权范围内
+    pub fn has_permission(&self, code: &str) -> bool {
+        if let Some(scopes) = &self.scopes
+            && !scopes.iter().any(|s| s == code)
+        {
+            return false;
+        }
+        self.is_admin() || self.permissions.iter().any(|p| p == code)
     }
 
     /// 强行退出登陆
     pub async fn logout(&self, state: &AppState) -> Result<(), Error> {
-        let redis = RedisUtil::new(state.redis.clone());
-        redis.del(&self.token).await?;
+        if self.is_api_key() {
+            return Err(Error::InvalidState("API Key 无需登出，请吊销该 key".into()));
+        }
+        match CONFIG.auth.mode {
+            AuthMode::Session => {
+                session::revoke(state, self.user_db.id, &self.session.id).await?;
+            }
+            AuthMode::Jwt => jwt::revoke(&self.session.id, state).await?,
+        }
         Ok(())
     }
 }
 
-impl FromRequestParts<AppState> for UserInfo {
+impl<S: Send + Sync> FromRequestParts<S> for UserInfo {
     type Rejection = Error;
 
     fn from_request_parts(
         parts: &mut Parts,
-        _state: &AppState,
+        _state: &S,
     ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
         Box::pin(async move {
-            Ok(parts
+            parts
                 .extensions
                 .get::<UserInfo>()
                 .cloned()
-                .ok_or(Error::NotLogin)?)
+                .ok_or(Error::NotLogin)
         })
     }
 }
+
+/// 权限校验提取器，所需的权限码以 state 形式由路由层传入
+///
+/// 用法：`post(handler).route_layer(RequirePermission::layer("endpoint:write"))`
+pub struct RequirePermission;
+
+impl RequirePermission {
+    /// 构造声明所需权限的路由层
+    pub fn layer(code: &'static str) -> FromExtractorLayer<Self, &'static str> {
+        from_extractor_with_state(code)
+    }
+}
+
+impl FromRequestParts<&'static str> for RequirePermission {
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        code: &&'static str,
+    ) -> Result<Self, Self::Rejection> {
+        let user = parts.extensions.get::<UserInfo>().ok_or(Error::NotLogin)?;
+        // 须修改密码的用户在修改前不能访问需要权限的接口
+        if user.must_change_password() {
+            return Err(Error::Unauthorized("请先修改密码".into()));
+        }
+        if !user.has_permission(code) {
+            info!(
+                "Permission denied: user={}, required={}",
+                user.user_db.username, code
+            );
+            return Err(Error::Unauthorized(format!("缺少权限: {}", code)));
+        }
+        Ok(Self)
+    }
+}
+
+/// 客户端信息（IP、User-Agent）
+///
+/// 连接来自受信任的反向代理时，IP 取 `X-Forwarded-For` 中最右侧的非代理地址或 `X-Real-IP`，
+/// 否则取连接地址，防止客户端伪造请求头
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn from_parts(parts: &Parts) -> Self {
+        let header_value = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|c| c.0.ip());
+        let proxies = &CONFIG.app.trusted_proxies;
+        let forwarded = match peer {
+            Some(peer) if is_trusted(proxies, peer) => header_value("x-forwarded-for")
+                .and_then(|v| forwarded_ip(proxies, &v))
+                .or_else(|| header_value("x-real-ip")),
+            _ => None,
+        };
+        Self {
+            ip: forwarded.or_else(|| peer.map(|ip| ip.to_string())),
+            user_agent: header_value(header::USER_AGENT.as_str()),
+        }
+    }
+}
+
+/// 自右向左跳过受信任代理，取第一个客户端地址；全部为代理时取最左侧地址
+fn forwarded_ip(proxies: &[String], value: &str) -> Option<String> {
+    let hops: Vec<&str> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    hops.iter()
+        .rev()
+        .find(|hop| hop.parse().map_or(true, |ip| !is_trusted(proxies, ip)))
+        .or(hops.first())
+        .map(|hop| hop.to_string())
+}
+
+/// 地址是否属于受信任代理，配置项为单个 IP 或 CIDR
+fn is_trusted(proxies: &[String], ip: IpAddr) -> bool {
+    proxies.iter().any(|proxy| {
+        let (addr, prefix) = match proxy.split_once('/') {
+            Some((addr, prefix)) => (addr, prefix.parse::<u32>().ok()),
+            None => (proxy.as_str(), None),
+        };
+        match (addr.trim().parse::<IpAddr>(), ip) {
+            (Ok(IpAddr::V4(net)), IpAddr::V4(ip)) => {
+                let bits = prefix.unwrap_or(32).min(32);
+                let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (Ok(IpAddr::V6(net)), IpAddr::V6(ip)) => {
+                let bits = prefix.unwrap_or(128).min(128);
+                let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    })
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(parts))
+    }
+}
+
+/// 反序列化并校验请求体的 JSON 提取器，JSON 格式错误与校验失败均转换为 `Error`，
+/// 与其他错误共用统一的响应格式
+///
+/// 用法：`ValidatedJson(parm): ValidatedJson<vo::QueryReq>`
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|e| Error::InvalidJson(e.body_text()))?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod test_client_info {
+    use super::*;
+
+    #[test]
+    fn test_trusted_proxy() {
+        let proxies = vec!["127.0.0.1".to_string(), "10.0.0.0/8".to_string()];
+        assert!(is_trusted(&proxies, "127.0.0.1".parse().unwrap()));
+        assert!(is_trusted(&proxies, "10.1.2.3".parse().unwrap()));
+        assert!(!is_trusted(&proxies, "192.168.1.1".parse().unwrap()));
+        assert!(!is_trusted(&proxies, "::1".parse().unwrap()));
+        assert!(!is_trusted(&[], "127.0.0.1".parse().unwrap()));
+
+        // 客户端自带的伪造地址位于左侧，取最右侧的非代理地址
+        let value = "1.1.1.1, 203.0.113.7, 10.0.0.2";
+        assert_eq!(
+            forwarded_ip(&proxies, value).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(
+            forwarded_ip(&proxies, "10.0.0.3, 10.0.0.2").as_deref(),
+            Some("10.0.0.3")
+        );
+    }
+}
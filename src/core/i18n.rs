@@ -0,0 +1,183 @@
+//! 消息本地化
+//!
+//! 代码中的提示信息统一以中文书写，并作为消息目录的 key；其他语言的目录位于 `locales/`，
+//! 编译时嵌入。目录中的 `{}` 为占位符，如 `"授权失败: {}"`，占位部分会继续递归翻译，
+//! 未收录的消息原样返回。
+//!
+//! 语言按请求选择：用户偏好优先，其次为 `Accept-Language`，最后为配置的默认语言
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use crate::core::{config::CONFIG, context};
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// 按语言标签匹配，只比较主语言（`en-GB` 视为 `en-US`）
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    /// 解析 `Accept-Language`，按权重选择第一个支持的语言
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut tags: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let tag = parts.next()?.trim();
+                let q = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && q > 0.0).then_some((tag, q))
+            })
+            .collect();
+        // 稳定排序，权重相同时保持原顺序
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+        tags.into_iter().find_map(|(tag, _)| Self::parse(tag))
+    }
+
+    /// 配置的默认语言
+    pub fn default_locale() -> Self {
+        Self::parse(&CONFIG.response.default_locale).unwrap_or(Locale::ZhCn)
+    }
+}
+
+/// 消息目录：完整消息与带占位符的模板分开存放，模板按字面长度降序匹配
+struct Catalog {
+    exact: HashMap<String, String>,
+    templates: Vec<(String, String)>,
+}
+
+impl Catalog {
+    fn load(source: &str) -> Self {
+        let entries: HashMap<String, String> =
+            serde_json::from_str(source).expect("加载消息目录失败!!!");
+        let (templates, exact): (HashMap<_, _>, HashMap<_, _>) =
+            entries.into_iter().partition(|(k, _)| k.contains("{}"));
+        let mut templates: Vec<(String, String)> = templates.into_iter().collect();
+        templates.sort_by_key(|(k, _)| std::cmp::Reverse(k.replace("{}", "").len()));
+        Self { exact, templates }
+    }
+}
+
+static EN_US: LazyLock<Catalog> =
+    LazyLock::new(|| Catalog::load(include_str!("../../locales/en-US.json")));
+
+/// 按当前请求的语言翻译消息
+pub fn t(msg: &str) -> String {
+    translate(context::locale(), msg)
+}
+
+/// 翻译消息到指定语言
+pub fn translate(locale: Locale, msg: &str) -> String {
+    let catalog = match locale {
+        Locale::ZhCn => return msg.to_string(),
+        Locale::EnUs => &*EN_US,
+    };
+    lookup(catalog, msg).unwrap_or_else(|| msg.to_string())
+}
+
+fn lookup(catalog: &Catalog, msg: &str) -> Option<String> {
+    if let Some(translated) = catalog.exact.get(msg) {
+        return Some(translated.clone());
+    }
+    catalog.templates.iter().find_map(|(source, target)| {
+        let captures = match_template(source, msg)?;
+        let mut result = String::new();
+        let mut parts = target.split("{}");
+        result.push_str(parts.next().unwrap_or_default());
+        for (capture, part) in captures.into_iter().zip(parts) {
+            result.push_str(&lookup(catalog, capture).unwrap_or_else(|| capture.to_string()));
+            result.push_str(part);
+        }
+        Some(result)
+    })
+}
+
+/// 按模板匹配消息，返回各占位符对应的内容
+fn match_template<'a>(template: &str, msg: &'a str) -> Option<Vec<&'a str>> {
+    let parts: Vec<&str> = template.split("{}").collect();
+    let (last, middle) = parts[1..].split_last()?;
+    let mut rest = msg.strip_prefix(parts[0])?;
+    let mut captures = Vec::with_capacity(parts.len() - 1);
+    for part in middle {
+        // 相邻占位符之间没有分隔文本时无法确定边界
+        if part.is_empty() {
+            return None;
+        }
+        let index = rest.find(part)?;
+        captures.push(&rest[..index]);
+        rest = &rest[index + part.len()..];
+    }
+    captures.push(rest.strip_suffix(last)?);
+    Some(captures)
+}
+
+#[cfg(test)]
+mod test_i18n {
+    use super::*;
+
+    #[test]
+    fn test_accept_language() {
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, en-GB;q=0.8, zh-CN;q=0.9"),
+            Some(Locale::ZhCn)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en-US,en;q=0.9"),
+            Some(Locale::EnUs)
+        );
+        assert_eq!(Locale::from_accept_language("fr, de;q=0.5"), None);
+        assert_eq!(
+            Locale::from_accept_language("zh;q=0, en"),
+            Some(Locale::EnUs)
+        );
+    }
+
+    #[test]
+    fn test_translate() {
+        let en = Locale::EnUs;
+        assert_eq!(
+            translate(en, "账号或密码错误"),
+            "Incorrect username or password"
+        );
+        // 模板嵌套：外层前缀与内层消息分别翻译
+        assert_eq!(
+            translate(en, "授权失败: 缺少权限: user:write"),
+            "Authorization failed: Missing permission: user:write"
+        );
+        assert_eq!(
+            translate(en, "密码长度必须在8到64个字符之间"),
+            "Password must be between 8 and 64 characters"
+        );
+        assert_eq!(
+            translate(en, "密码须包含大写字母"),
+            "Password must contain an uppercase letter"
+        );
+        assert_eq!(
+            translate(en, "构建查询失败: 字段 role 的值 \"abc\" 无效，应为整数"),
+            "Failed to build query: Field role has invalid value \"abc\", expected an integer"
+        );
+        // 未收录的消息原样返回
+        assert_eq!(translate(en, "未收录的消息"), "未收录的消息");
+        assert_eq!(translate(Locale::ZhCn, "账号已禁用"), "账号已禁用");
+    }
+}
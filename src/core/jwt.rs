@@ -0,0 +1,288 @@
+//! 无状态 JWT 认证
+//!
+//! - access token 携带用户身份与权限，请求时直接从 claims 重建 `UserInfo`
+//! - refresh token 用于换取新的 token 对，换取后旧的一对立即作废；
+//!   以 `jti` 原子占位，同一 refresh token 并发换取时仅一次成功
+//! - 同一对 token 共享 `sid`，登出时将 `sid` 写入 Redis 黑名单
+//! - 用户被禁用或信息变更时记录吊销时间，此前签发的全部 token 失效
+//! - 模拟登录的 token 在 `act` 中携带管理员，管理员的 token 被作废时一并失效
+
+use std::{sync::LazyLock, time::Duration};
+
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState, Error,
+    core::{
+        config::CONFIG,
+        constant,
+        extractor::{Impersonator, UserInfo},
+        session::SessionMeta,
+    },
+    entity,
+    utils::{RedisUtil, random},
+};
+
+/// token 类型
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Claims {
+    /// 用户 ID
+    pub sub: i64,
+    /// 会话 ID，access 与 refresh 共享
+    pub sid: String,
+    pub typ: TokenType,
+    /// token ID，refresh token 换取时据此保证只能使用一次
+    #[serde(default)]
+    pub jti: String,
+    pub iss: String,
+    pub iat: i64,
+    /// 签发时间 毫秒，与用户吊销时间比较，避免同一秒内签发与吊销无法区分
+    #[serde(default)]
+    pub iat_ms: i64,
+    pub exp: i64,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub role: i32,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// 须先修改密码
+    #[serde(default)]
+    pub must_change_password: bool,
+    /// 模拟登录时的真实操作人，access 与 refresh 均携带，刷新后保持模拟状态
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Impersonator>,
+    /// 语言偏好
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+/// 签名与验签密钥，首次使用时根据配置加载
+struct JwtKeys {
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+static KEYS: LazyLock<JwtKeys> = LazyLock::new(|| JwtKeys::load().expect("加载 JWT 密钥失败!!!"));
+
+impl JwtKeys {
+    fn load() -> Result<Self, Error> {
+        let conf = &CONFIG.auth.jwt;
+        match conf.algorithm.as_str() {
+            "HS256" => {
+                if conf.secret.is_empty() {
+                    return Err(Error::InvalidState("未配置 auth.jwt.secret".into()));
+                }
+                Ok(Self {
+                    algorithm: Algorithm::HS256,
+                    encoding: EncodingKey::from_secret(conf.secret.as_bytes()),
+                    decoding: DecodingKey::from_secret(conf.secret.as_bytes()),
+                })
+            }
+            "EdDSA" => {
+                let read = |path: &str| {
+                    std::fs::read(path).map_err(|e| {
+                        Error::io(format!("读取密钥文件 {} 失败", path)).with_source(e)
+                    })
+                };
+                let private_key = read(&conf.private_key)?;
+                let public_key = read(&conf.public_key)?;
+                Ok(Self {
+                    algorithm: Algorithm::EdDSA,
+                    encoding: EncodingKey::from_ed_pem(&private_key)
+                        .map_err(|e| Error::InvalidState(format!("EdDSA 私钥无效:{}", e)))?,
+                    decoding: DecodingKey::from_ed_pem(&public_key)
+                        .map_err(|e| Error::InvalidState(format!("EdDSA 公钥无效:{}", e)))?,
+                })
+            }
+            other => Err(Error::InvalidState(format!("不支持的 JWT 算法: {}", other))),
+        }
+    }
+}
+
+/// 启动时校验密钥配置，避免首个请求才发现配置错误
+pub fn init() {
+    LazyLock::force(&KEYS);
+}
+
+/// 签发的 token 对
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// 为用户签发新的 token 对
+pub fn issue(
+    user_db: &entity::user::Model,
+    permissions: &[String],
+    act: Option<&Impersonator>,
+) -> Result<TokenPair, Error> {
+    let now_ms = chrono::Local::now().timestamp_millis();
+    let now = now_ms / 1000;
+    let conf = &CONFIG.auth.jwt;
+    let sid = random::token(16);
+    let access = Claims {
+        sub: user_db.id,
+        sid: sid.clone(),
+        typ: TokenType::Access,
+        jti: random::token(16),
+        iss: conf.issuer.clone(),
+        iat: now,
+        iat_ms: now_ms,
+        exp: now + conf.access_ttl as i64,
+        username: user_db.username.clone(),
+        role: user_db.role,
+        permissions: permissions.to_vec(),
+        must_change_password: user_db.must_change_password == 1,
+        act: act.cloned(),
+        locale: user_db.locale.clone(),
+    };
+    let refresh = Claims {
+        sub: user_db.id,
+        sid,
+        typ: TokenType::Refresh,
+        jti: random::token(16),
+        iss: conf.issuer.clone(),
+        iat: now,
+        iat_ms: now_ms,
+        exp: now + conf.refresh_ttl as i64,
+        username: String::new(),
+        role: user_db.role,
+        permissions: vec![],
+        must_change_password: false,
+        act: act.cloned(),
+        locale: None,
+    };
+    Ok(TokenPair {
+        access_token: encode(&access)?,
+        refresh_token: encode(&refresh)?,
+    })
+}
+
+/// 校验 token 的签名、有效期、类型以及是否已登出
+pub async fn verify(token: &str, typ: TokenType, state: &AppState) -> Result<Claims, Error> {
+    let mut validation = Validation::new(KEYS.algorithm);
+    validation.set_issuer(&[&CONFIG.auth.jwt.issuer]);
+    let claims = jsonwebtoken::decode::<Claims>(token, &KEYS.decoding, &validation)
+        .map_err(|_| Error::NotLogin)?
+        .claims;
+    if claims.typ != typ {
+        return Err(Error::NotLogin);
+    }
+
+    let redis = RedisUtil::new(state.redis.clone());
+    let mut keys = vec![deny_key(&claims.sid), user_revoke_key(claims.sub)];
+    if let Some(act) = &claims.act {
+        keys.push(user_revoke_key(act.user_id));
+    }
+    let values = redis.mget::<i64>(&keys).await?;
+    if values[0].is_some() {
+        return Err(Error::NotLogin);
+    }
+    if values[1..]
+        .iter()
+        .any(|v| v.is_some_and(|revoked_at| revoked_at > claims.iat_ms))
+    {
+        return Err(Error::NotLogin);
+    }
+    Ok(claims)
+}
+
+/// 将会话加入黑名单，access 与 refresh 同时失效
+pub async fn revoke(sid: &str, state: &AppState) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .set_with_expire(
+            &deny_key(sid),
+            1,
+            Duration::from_secs(CONFIG.auth.jwt.refresh_ttl),
+        )
+        .await
+}
+
+/// 占用 refresh token，已被使用过（包括并发请求）时返回 `NotLogin`
+pub async fn consume(claims: &Claims, state: &AppState) -> Result<(), Error> {
+    if claims.jti.is_empty() {
+        return Err(Error::NotLogin);
+    }
+    let remaining = claims.exp - chrono::Local::now().timestamp();
+    let redis = RedisUtil::new(state.redis.clone());
+    let unused = redis
+        .set_if_absent_with_expire(
+            &format!("{}:{}", constant::JWT_USED_KEY, claims.jti),
+            1,
+            Duration::from_secs(remaining.max(1) as u64),
+        )
+        .await?;
+    if !unused {
+        return Err(Error::NotLogin);
+    }
+    Ok(())
+}
+
+/// 作废用户此前签发的全部 token
+pub async fn revoke_user(user_id: i64, state: &AppState) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .set_with_expire(
+            &user_revoke_key(user_id),
+            chrono::Local::now().timestamp_millis(),
+            Duration::from_secs(CONFIG.auth.jwt.refresh_ttl),
+        )
+        .await
+}
+
+/// 从 access token 的 claims 重建用户信息（不访问数据库）
+pub fn user_from_claims(token: &str, claims: Claims) -> UserInfo {
+    let issued_at = chrono::DateTime::from_timestamp(claims.iat, 0)
+        .map(|t| t.with_timezone(&chrono::Local).naive_local())
+        .unwrap_or_default();
+    UserInfo {
+        token: token.to_string(),
+        user_db: entity::user::Model {
+            id: claims.sub,
+            username: claims.username,
+            password: String::new(),
+            salt: None,
+            role: claims.role,
+            enable_flag: 1,
+            must_change_password: claims.must_change_password as i8,
+            locale: claims.locale,
+            created_at: None,
+            updated_at: None,
+        },
+        permissions: claims.permissions,
+        session: SessionMeta {
+            id: claims.sid,
+            issued_at,
+            last_seen: chrono::Local::now().naive_local(),
+            ip: None,
+            user_agent: None,
+        },
+        refresh_token: None,
+        scopes: None,
+        impersonator: claims.act,
+    }
+}
+
+fn encode(claims: &Claims) -> Result<String, Error> {
+    jsonwebtoken::encode(&Header::new(KEYS.algorithm), claims, &KEYS.encoding)
+        .map_err(|e| Error::unknown("签发 JWT 失败").with_source(e))
+}
+
+fn deny_key(sid: &str) -> String {
+    format!("{}:{}", constant::JWT_DENY_KEY, sid)
+}
+
+fn user_revoke_key(user_id: i64) -> String {
+    format!("{}:{}", constant::JWT_USER_REVOKE_KEY, user_id)
+}
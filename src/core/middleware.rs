@@ -1,15 +1,23 @@
 use axum::{
     extract::{Request, State},
-    http::HeaderValue,
+    http::{HeaderMap, HeaderValue, header},
     middleware::Next,
     response::IntoResponse,
 };
-use tracing::{Instrument, info_span};
+use tracing::{Instrument, Span, info, info_span};
 use uuid::Uuid;
 
 use crate::{
-    core::{constant, extractor::UserInfo, state::AppState},
-    error::Error,
+    core::{
+        constant,
+        context::{self, Actor, RequestContext},
+        cookie,
+        extractor::{ClientInfo, UserInfo},
+        i18n::Locale,
+        state::AppState,
+    },
+    error::{Error, PROBLEM_JSON},
+    service::api_key_service,
 };
 
 /// 日志中间件
@@ -25,13 +33,39 @@ pub async fn log_middleware(mut req: Request, next: Next) -> impl IntoResponse {
     // 2. 将 trace_id 放入请求扩展，供 handler 提取
     req.extensions_mut().insert(trace_id.clone());
 
-    // 3. 使用 tracing::instrument 将 trace_id 注入 Span
-    let mut response = next
-        .run(req)
-        .instrument(info_span!(constant::TRACE_LOG, trace_id = %trace_id))
-        .await;
+    // 3. 记录请求上下文，供审计等深层调用读取
+    let (parts, body) = req.into_parts();
+    let ctx = RequestContext {
+        trace_id: trace_id.clone(),
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        ip: ClientInfo::from_parts(&parts).ip,
+        accept_problem: parts
+            .headers
+            .get(header::ACCEPT)
+            .and_then(|h| h.to_str().ok())
+            .is_some_and(|accept| accept.contains(PROBLEM_JSON)),
+    };
+    let locale = parts
+        .headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_else(Locale::default_locale);
+    let req = Request::from_parts(parts, body);
+
+    // 4. 使用 tracing::instrument 将 trace_id 注入 Span
+    let mut response = context::scope_request(
+        ctx,
+        context::scope_locale(
+            locale,
+            next.run(req)
+                .instrument(info_span!(constant::TRACE_LOG, trace_id = %trace_id)),
+        ),
+    )
+    .await;
 
-    // 4. 在响应头中添加同一份 trace_id
+    // 5. 在响应头中添加同一份 trace_id
     response.headers_mut().insert(
         constant::TRACE_HEADER,
         HeaderValue::from_str(&trace_id).unwrap(),
@@ -46,21 +80,67 @@ pub async fn user_middleware(
     mut req: Request,
     next: Next,
 ) -> Result<impl IntoResponse, Error> {
-    // 1. 从请求头提取 token
-    let token = req
-        .headers()
-        .get(constant::AUTH_HEADER)
-        .and_then(|h| h.to_str().ok())
-        .ok_or(Error::NotLogin)?;
-
-    // 2. token 格式校验
-    let user = UserInfo::from_token(token, &state).await?;
+    let headers = req.headers();
+    let api_key = header_value(headers, constant::API_KEY_HEADER);
+    let token = header_value(headers, constant::AUTH_HEADER);
+    // cookie 模式下未携带 Token 请求头时读取 cookie，此时须通过 CSRF 校验
+    let cookie_token = match (&api_key, &token) {
+        (None, None) => cookie::session_token(headers),
+        _ => None,
+    };
+    if cookie_token.is_some() {
+        cookie::verify_csrf(req.method(), headers)?;
+    }
+    let token = token.or(cookie_token);
 
-    // 3.续时长
-    user.refresh_session(&state).await?;
+    // 1. 机器客户端使用 API Key，其余从请求头或 cookie 提取 token
+    let user = if let Some(key) = api_key {
+        api_key_service::authenticate(&key, &state).await?
+    } else {
+        let token = token.ok_or(Error::NotLogin)?;
+        // 2. 校验 token（会话模式下同时续时长）
+        UserInfo::authenticate(&token, &state).await?
+    };
 
-    // 4. 将用户信息放入请求扩展，供 handler 提取
+    // 3. 将用户信息放入请求扩展，供 handler 提取；操作人写入上下文供审计使用
+    let actor = Actor {
+        user_id: user.user_db.id,
+        username: user.user_db.username.clone(),
+        impersonator: user
+            .impersonator
+            .as_ref()
+            .map(|admin| (admin.user_id, admin.username.clone())),
+    };
+    // 4. 模拟登录的请求在日志中标记真实操作人
+    let span = match &user.impersonator {
+        Some(admin) => {
+            info!(
+                "Impersonated request: {} as {}",
+                admin.username, user.user_db.username
+            );
+            info_span!("impersonated", by = %admin.username, as_user = %user.user_db.username)
+        }
+        None => Span::none(),
+    };
+    // 5. 用户设置了语言偏好时覆盖 Accept-Language
+    let locale = user
+        .user_db
+        .locale
+        .as_deref()
+        .and_then(Locale::parse)
+        .unwrap_or_else(context::locale);
     req.extensions_mut().insert(user);
 
-    Ok(next.run(req).await)
+    Ok(context::scope_actor(
+        actor,
+        context::scope_locale(locale, next.run(req).instrument(span)),
+    )
+    .await)
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string())
 }
@@ -1,6 +1,11 @@
 pub(crate) mod config;
 pub(crate) mod constant;
+pub(crate) mod context;
+pub(crate) mod cookie;
 pub(crate) mod extractor;
+pub(crate) mod i18n;
+pub(crate) mod jwt;
 pub(crate) mod log;
 pub(crate) mod middleware;
+pub(crate) mod session;
 pub(crate) mod state;
@@ -0,0 +1,272 @@
+//! 会话存储
+//!
+//! Redis 中的数据结构：
+//! - `Session:{token}`：会话内容（UserInfo JSON），带过期时间
+//! - `SessionIndex:{user_id}`：用户会话索引（哈希 session_id -> token），用于列出、吊销会话
+//! - `SessionSeen:{user_id}`：会话最近访问时间（哈希 session_id -> 时间），请求时单独更新，
+//!   不回写会话内容，避免覆盖并发的用户信息同步
+
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState, Error,
+    core::{
+        config::{AuthMode, CONFIG},
+        constant,
+        extractor::{ClientInfo, UserInfo},
+        jwt,
+    },
+    dao::PermissionDao,
+    entity,
+    utils::{RedisUtil, random},
+};
+
+/// 会话元信息
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMeta {
+    /// 会话 ID，对外展示与吊销使用，不等同于 token
+    pub id: String,
+    pub issued_at: NaiveDateTime,
+    pub last_seen: NaiveDateTime,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl SessionMeta {
+    pub fn new(client: &ClientInfo) -> Self {
+        let now = chrono::Local::now().naive_local();
+        Self {
+            id: random::token(8),
+            issued_at: now,
+            last_seen: now,
+            ip: client.ip.clone(),
+            user_agent: client.user_agent.clone(),
+        }
+    }
+}
+
+/// 会话内容的 redis key
+pub fn session_key(token: &str) -> String {
+    format!("{}:{}", constant::SESSION_KEY, token)
+}
+
+/// 用户会话索引的 redis key
+fn index_key(user_id: i64) -> String {
+    format!("{}:{}", constant::SESSION_INDEX_KEY, user_id)
+}
+
+/// 会话最近访问时间的 redis key
+fn seen_key(user_id: i64) -> String {
+    format!("{}:{}", constant::SESSION_SEEN_KEY, user_id)
+}
+
+/// 写入新会话，并登记到用户会话索引，同时清理已过期的索引项
+pub async fn create(state: &AppState, user: &UserInfo) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .set_with_expire(
+            &session_key(&user.token),
+            serde_json::to_string(user).unwrap(),
+            Duration::from_secs(constant::EXPIRATION_SECS),
+        )
+        .await?;
+    redis
+        .hset(&index_key(user.user_db.id), &user.session.id, &user.token)
+        .await?;
+    list(state, user.user_db.id).await?;
+    Ok(())
+}
+
+/// 覆盖已存在的会话并刷新过期时间；会话已被吊销或过期时返回 NotLogin，避免被重新写回
+pub async fn save(state: &AppState, user: &UserInfo) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let saved = redis
+        .set_if_exists_with_expire(
+            &session_key(&user.token),
+            serde_json::to_string(user).unwrap(),
+            Duration::from_secs(constant::EXPIRATION_SECS),
+        )
+        .await?;
+    if !saved {
+        return Err(Error::NotLogin);
+    }
+    Ok(())
+}
+
+/// 请求时续期会话并记录最近访问时间；只重置有效期而不重写会话内容，
+/// 会话已被吊销或过期时返回 NotLogin
+pub async fn touch(state: &AppState, user: &UserInfo) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let alive = redis
+        .expire(
+            &session_key(&user.token),
+            Duration::from_secs(constant::EXPIRATION_SECS),
+        )
+        .await?;
+    if !alive {
+        return Err(Error::NotLogin);
+    }
+    redis
+        .hset(
+            &seen_key(user.user_db.id),
+            &user.session.id,
+            user.session.last_seen.and_utc().timestamp_millis(),
+        )
+        .await
+}
+
+/// 会话管理仅在会话模式下可用
+fn ensure_session_mode() -> Result<(), Error> {
+    if CONFIG.auth.mode != AuthMode::Session {
+        return Err(Error::InvalidState("JWT 模式下不支持会话管理".into()));
+    }
+    Ok(())
+}
+
+/// 列出用户的全部有效会话，顺带清理已过期的索引项
+pub async fn list(state: &AppState, user_id: i64) -> Result<Vec<UserInfo>, Error> {
+    ensure_session_mode()?;
+    let redis = RedisUtil::new(state.redis.clone());
+    let index = redis.hgetall(&index_key(user_id)).await?;
+    let (session_ids, tokens): (Vec<String>, Vec<String>) = index.into_iter().unzip();
+    let keys: Vec<String> = tokens.iter().map(|t| session_key(t)).collect();
+    let values = redis.mget::<String>(&keys).await?;
+    let seen = redis.hgetall(&seen_key(user_id)).await?;
+
+    let mut sessions = Vec::new();
+    let mut stale = Vec::new();
+    for (session_id, value) in session_ids.into_iter().zip(values) {
+        match value.and_then(|v| serde_json::from_str::<UserInfo>(&v).ok()) {
+            Some(mut user) => {
+                let last_seen = seen
+                    .get(&session_id)
+                    .and_then(|ms| ms.parse().ok())
+                    .and_then(chrono::DateTime::from_timestamp_millis);
+                if let Some(last_seen) = last_seen {
+                    user.session.last_seen = last_seen.naive_utc();
+                }
+                sessions.push(user);
+            }
+            None => stale.push(session_id),
+        }
+    }
+    redis.hdel(&index_key(user_id), &stale).await?;
+    redis.hdel(&seen_key(user_id), &stale).await?;
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.session.last_seen));
+    Ok(sessions)
+}
+
+/// 吊销用户的指定会话，返回是否存在该会话
+pub async fn revoke(state: &AppState, user_id: i64, session_id: &str) -> Result<bool, Error> {
+    ensure_session_mode()?;
+    let redis = RedisUtil::new(state.redis.clone());
+    let key = index_key(user_id);
+    let Some(token) = redis.hget::<String>(&key, session_id).await? else {
+        return Ok(false);
+    };
+    redis.del(&session_key(&token)).await?;
+    redis.hdel(&key, &[session_id.to_string()]).await?;
+    redis
+        .hdel(&seen_key(user_id), &[session_id.to_string()])
+        .await?;
+    Ok(true)
+}
+
+/// 查找用户指定会话的 token，会话已过期或被吊销时返回 None
+pub async fn token_of(
+    state: &AppState,
+    user_id: i64,
+    session_id: &str,
+) -> Result<Option<String>, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let Some(token) = redis
+        .hget::<String>(&index_key(user_id), session_id)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let alive = redis.ttl(&session_key(&token)).await?.is_some();
+    Ok(alive.then_some(token))
+}
+
+/// 吊销用户的全部会话，`except` 为需要保留的会话 ID，返回吊销数量
+pub async fn revoke_all(
+    state: &AppState,
+    user_id: i64,
+    except: Option<&str>,
+) -> Result<u64, Error> {
+    ensure_session_mode()?;
+    let redis = RedisUtil::new(state.redis.clone());
+    let key = index_key(user_id);
+    let (session_ids, keys): (Vec<String>, Vec<String>) = redis
+        .hgetall(&key)
+        .await?
+        .into_iter()
+        .filter(|(session_id, _)| Some(session_id.as_str()) != except)
+        .map(|(session_id, token)| (session_id, session_key(&token)))
+        .unzip();
+    redis.del_many(&keys).await?;
+    redis.hdel(&key, &session_ids).await?;
+    redis.hdel(&seen_key(user_id), &session_ids).await?;
+    Ok(session_ids.len() as u64)
+}
+
+/// 用户信息变更后同步其全部在线会话：已禁用则全部吊销，否则刷新会话中的用户信息与权限。
+/// JWT 无法原地更新，统一作废此前签发的 token
+pub async fn sync_user(state: &AppState, user_db: &entity::user::Model) -> Result<(), Error> {
+    match CONFIG.auth.mode {
+        AuthMode::Jwt => jwt::revoke_user(user_db.id, state).await,
+        AuthMode::Session => {
+            if user_db.enable_flag != 1 {
+                revoke_all(state, user_db.id, None).await?;
+                return Ok(());
+            }
+            let permissions = PermissionDao::query_codes_by_role(&state.db, user_db.role).await?;
+            for mut user in list(state, user_db.id).await? {
+                user.user_db = user_db.clone();
+                user.permissions = permissions.clone();
+                // 会话恰好过期或被吊销时忽略
+                match save(state, &user).await {
+                    Ok(()) | Err(Error::NotLogin) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 凭证变更后保留当前会话、吊销其余会话，返回更新后的当前用户信息。
+/// JWT 无法单独保留当前 token，作废全部后重新签发
+pub async fn rotate(
+    state: &AppState,
+    mut user: UserInfo,
+    user_db: entity::user::Model,
+    client: &ClientInfo,
+) -> Result<UserInfo, Error> {
+    match CONFIG.auth.mode {
+        AuthMode::Jwt => {
+            jwt::revoke_user(user_db.id, state).await?;
+            UserInfo::login(user_db, client, state).await
+        }
+        AuthMode::Session => {
+            user.user_db = user_db;
+            save(state, &user).await?;
+            revoke_all(state, user.user_db.id, Some(&user.session.id)).await?;
+            Ok(user)
+        }
+    }
+}
+
+/// 吊销用户的全部会话（两种认证方式通用）
+pub async fn revoke_user(state: &AppState, user_id: i64) -> Result<(), Error> {
+    match CONFIG.auth.mode {
+        AuthMode::Jwt => jwt::revoke_user(user_id, state).await,
+        AuthMode::Session => revoke_all(state, user_id, None).await.map(|_| ()),
+    }
+}
@@ -5,7 +5,14 @@ use bb8_redis::RedisConnectionManager;
 use tokio::sync::Mutex;
 use tracing::info;
 
-use crate::{Error, core::config::CONFIG, utils::RedisUtil};
+use crate::{
+    Error,
+    core::{
+        config::{AuthMode, CONFIG},
+        jwt,
+    },
+    utils::RedisUtil,
+};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -28,6 +35,12 @@ impl AppState {
         // 初始化 Redis
         let redis = Self::init_redis().await?;
 
+        // JWT 模式下提前加载密钥
+        if CONFIG.auth.mode == AuthMode::Jwt {
+            jwt::init();
+            info!("Auth mode: jwt ({})", CONFIG.auth.jwt.algorithm);
+        }
+
         Ok(AppState {
             db,
             redis,
@@ -71,3 +84,15 @@ impl AppState {
         Ok(pool)
     }
 }
+
+#[cfg(test)]
+impl AppState {
+    /// 测试用状态：模拟数据库与内存 Redis
+    pub async fn mock(db: sea_orm::MockDatabase) -> Self {
+        AppState {
+            db: db.into_connection(),
+            redis: crate::utils::mock_redis::pool().await,
+            counter: Arc::new(Mutex::new(0)),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    Error,
+    dao::{AuditAction, AuditDao},
+    entity,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityName, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+
+pub struct ApiKeyDao;
+
+impl ApiKeyDao {
+    pub async fn insert(
+        db: &sea_orm::DbConn,
+        model: entity::api_key::ActiveModel,
+    ) -> Result<entity::api_key::Model, Error> {
+        let created = model.insert(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Create,
+            entity::api_key::Entity.table_name(),
+            created.id,
+            None,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
+    }
+
+    /// 按 key 摘要查询
+    pub async fn query_by_hash(
+        db: &sea_orm::DbConn,
+        key_hash: &str,
+    ) -> Result<Option<entity::api_key::Model>, Error> {
+        Ok(entity::api_key::Entity::find()
+            .filter(entity::api_key::Column::KeyHash.eq(key_hash))
+            .one(db)
+            .await?)
+    }
+
+    /// 查询用户的全部 API Key，新创建的在前
+    pub async fn query_by_user(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+    ) -> Result<Vec<entity::api_key::Model>, Error> {
+        Ok(entity::api_key::Entity::find()
+            .filter(entity::api_key::Column::UserId.eq(user_id))
+            .order_by_desc(entity::api_key::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// 记录最近使用时间
+    pub async fn touch(db: &sea_orm::DbConn, id: i64) -> Result<(), Error> {
+        let model = entity::api_key::ActiveModel {
+            id: Set(id),
+            last_used_at: Set(Some(chrono::Local::now().naive_local())),
+            ..Default::default()
+        };
+        model.update(db).await?;
+        Ok(())
+    }
+
+    /// 删除用户的指定 API Key，返回删除条数
+    pub async fn delete_by_user(db: &sea_orm::DbConn, user_id: i64, id: i64) -> Result<u64, Error> {
+        let Some(before) = entity::api_key::Entity::find_by_id(id)
+            .filter(entity::api_key::Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+        else {
+            return Ok(0);
+        };
+        let result = entity::api_key::Entity::delete_by_id(id).exec(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Delete,
+            entity::api_key::Entity.table_name(),
+            id,
+            Some(&before),
+            None,
+        )
+        .await;
+        Ok(result.rows_affected)
+    }
+}
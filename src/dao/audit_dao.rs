@@ -0,0 +1,150 @@
+use sea_orm::{ActiveModelTrait, ActiveValue::Set};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use crate::{core::context, entity};
+
+/// 审计操作类型
+#[derive(Debug, Clone, Copy)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Impersonate,
+    ImpersonateStop,
+}
+
+impl AuditAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Impersonate => "impersonate",
+            AuditAction::ImpersonateStop => "impersonate_stop",
+        }
+    }
+}
+
+/// 敏感字段，只记录是否变更，不记录取值
+const MASKED_FIELDS: &[&str] = &["password", "salt", "secret", "keyHash", "recoveryCodes"];
+const MASK: &str = "******";
+
+/// 每次更新都会变化的字段，不计入差异
+const IGNORED_FIELDS: &[&str] = &["updatedAt", "updateTime"];
+
+pub struct AuditDao;
+
+impl AuditDao {
+    /// 记录一次数据变更，操作人与请求信息从请求上下文读取。
+    /// 审计写入失败只记录日志，不影响已完成的业务操作
+    pub async fn record<M: Serialize>(
+        db: &sea_orm::DbConn,
+        action: AuditAction,
+        entity: &str,
+        entity_id: impl ToString,
+        before: Option<&M>,
+        after: Option<&M>,
+    ) {
+        let snapshot = |m: Option<&M>| m.and_then(|m| serde_json::to_value(m).ok());
+        let (before, after) = match action {
+            AuditAction::Update => match diff(snapshot(before), snapshot(after)) {
+                Some((before, after)) => (Some(before), Some(after)),
+                // 没有实际变更
+                None => return,
+            },
+            _ => (snapshot(before).map(mask), snapshot(after).map(mask)),
+        };
+
+        let request = context::request().unwrap_or_default();
+        let actor = context::actor();
+        let impersonator = actor.as_ref().and_then(|a| a.impersonator.clone());
+        let model = entity::audit_log::ActiveModel {
+            trace_id: Set(Some(request.trace_id).filter(|s| !s.is_empty())),
+            actor_id: Set(actor.as_ref().map(|a| a.user_id)),
+            actor_name: Set(actor.map(|a| a.username)),
+            impersonator_id: Set(impersonator.as_ref().map(|(id, _)| *id)),
+            impersonator_name: Set(impersonator.map(|(_, name)| name)),
+            ip: Set(request.ip),
+            method: Set(Some(request.method).filter(|s| !s.is_empty())),
+            path: Set(Some(request.path).filter(|s| !s.is_empty())),
+            action: Set(action.as_str().to_string()),
+            entity: Set(entity.to_string()),
+            entity_id: Set(entity_id.to_string()),
+            before_data: Set(before),
+            after_data: Set(after),
+            ..Default::default()
+        };
+        if let Err(e) = model.insert(db).await {
+            warn!(
+                "Write audit log failed: action={}, entity={}, err={}",
+                action.as_str(),
+                entity,
+                e
+            );
+        }
+    }
+}
+
+fn mask(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let v = if MASKED_FIELDS.contains(&k.as_str()) && !v.is_null() {
+                        Value::from(MASK)
+                    } else {
+                        v
+                    };
+                    (k, v)
+                })
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// 计算更新前后的差异，只保留发生变化的字段；无变化时返回 None
+fn diff(before: Option<Value>, after: Option<Value>) -> Option<(Value, Value)> {
+    let (Some(Value::Object(before)), Some(Value::Object(after))) = (before, after) else {
+        return None;
+    };
+    let mut changed_before = Map::new();
+    let mut changed_after = Map::new();
+    for (key, new) in after {
+        if IGNORED_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        let old = before.get(&key).cloned().unwrap_or(Value::Null);
+        if old != new {
+            changed_before.insert(key.clone(), old);
+            changed_after.insert(key, new);
+        }
+    }
+    if changed_after.is_empty() {
+        return None;
+    }
+    Some((
+        mask(Value::Object(changed_before)),
+        mask(Value::Object(changed_after)),
+    ))
+}
+
+#[cfg(test)]
+mod test_audit {
+    use super::*;
+
+    #[test]
+    fn test_diff_keeps_changed_fields_and_masks_secrets() {
+        let before = serde_json::json!({"id": 1, "name": "a", "password": "x", "updatedAt": "t1"});
+        let after = serde_json::json!({"id": 1, "name": "b", "password": "y", "updatedAt": "t2"});
+        let (before, after) = diff(Some(before), Some(after)).unwrap();
+        assert_eq!(before, serde_json::json!({"name": "a", "password": MASK}));
+        assert_eq!(after, serde_json::json!({"name": "b", "password": MASK}));
+
+        let same = serde_json::json!({"id": 1, "updatedAt": "t1"});
+        let touched = serde_json::json!({"id": 1, "updatedAt": "t2"});
+        assert!(diff(Some(same), Some(touched)).is_none());
+    }
+}
@@ -1,16 +1,28 @@
 //! 通用查询与分页请求体定义
 //!
 //! 该模块包含：
-//! - 根据主键删除单条记录
+//! - 根据主键删除单条记录（记录审计日志）
 //! - 根据主键查询单条记录
 //! - 根据请求条件分页查询
 //! - 根据请求条件查询全量数据（不分页）
 //!
 //! 为各业务模块复用。
 
-use sea_orm::{EntityTrait, FromQueryResult, PaginatorTrait, PrimaryKeyTrait};
+use sea_orm::{
+    EntityTrait, FromQueryResult, PaginatorTrait, PrimaryKeyTrait, QuerySelect,
+    sea_query::{FromValueTuple, IntoValueTuple},
+};
+use serde::Serialize;
 
-use crate::{dao::query_build::build_query, error::Error, vo};
+use crate::{
+    dao::{
+        AuditAction, AuditDao, QueryPolicy,
+        query_build::{build_cursor_query, build_query, cursor_keys, encode_cursor},
+        query_expand::Projection,
+    },
+    error::Error,
+    vo,
+};
 
 /// 根据主键删除单条记录
 ///
@@ -27,8 +39,29 @@ pub async fn delete_by_id<E>(
 ) -> Result<u64, Error>
 where
     E: EntityTrait,
+    E::Model: Serialize,
 {
-    let result = E::delete_by_id(id).exec(db).await?;
+    type Key<E> = <<E as EntityTrait>::PrimaryKey as PrimaryKeyTrait>::ValueType;
+    let entity_id = format!("{:?}", id);
+    // 主键类型未实现 Clone，借助 ValueTuple 复制
+    let key = id.into_value_tuple();
+    let before = E::find_by_id(Key::<E>::from_value_tuple(key.clone()))
+        .one(db)
+        .await?;
+    let result = E::delete_by_id(Key::<E>::from_value_tuple(key))
+        .exec(db)
+        .await?;
+    if result.rows_affected > 0 {
+        AuditDao::record(
+            db,
+            AuditAction::Delete,
+            E::default().table_name(),
+            entity_id,
+            before.as_ref(),
+            None,
+        )
+        .await;
+    }
     Ok(result.rows_affected)
 }
 
@@ -56,26 +89,59 @@ where
 ///
 /// # 参数
 /// * `db` - 数据库连接对象
-/// * `req` - 查询请求体（包含过滤条件、页码、页大小等）
+/// * `req` - 查询请求体（包含过滤条件、页码、页大小、返回列、展开关联等）
 ///
 /// # 返回
-/// * `Ok(PageResp<E::Model>)` - 分页结果，含总数、页码、数据
+/// * `Ok(PageResp<Value>)` - 分页结果，含总数、页码、数据；数据仅含请求的可返回列及展开的关联
 /// * `Err(Error)` - 查询过程中发生的错误
 pub async fn query_by_page<E>(
     db: &sea_orm::DbConn,
     req: &vo::QueryReq,
-) -> Result<vo::PageResp<E::Model>, Error>
+) -> Result<vo::PageResp<serde_json::Value>, Error>
 where
-    E: EntityTrait,
+    E: QueryPolicy,
     E::Model: FromQueryResult + Sized + Send + Sync,
 {
+    let projection = Projection::<E>::new(req)?;
+    if req.mode == vo::PageMode::Cursor {
+        return query_by_cursor::<E>(db, req, &projection).await;
+    }
     let query = build_query::<E>(req)?;
-    let paginator = query.paginate(db, req.size);
+    let paginator = projection.select(query, &[]).paginate(db, req.size);
     let total = paginator.num_items().await?;
-    let data = paginator.fetch_page(req.page).await?;
+    let rows = paginator.fetch_page(req.page).await?;
+    let data = projection.finish(db, rows).await?;
     Ok(vo::PageResp::new(total, req.page, req.size, data))
 }
 
+/// 游标分页：多取一行判断是否还有下一页，仅在请求 withTotal 时统计总数
+async fn query_by_cursor<E>(
+    db: &sea_orm::DbConn,
+    req: &vo::QueryReq,
+    projection: &Projection<E>,
+) -> Result<vo::PageResp<serde_json::Value>, Error>
+where
+    E: QueryPolicy,
+    E::Model: FromQueryResult + Sized + Send + Sync,
+{
+    let keys = cursor_keys::<E>(req)?;
+    let total = match req.with_total {
+        true => Some(build_query::<E>(req)?.count(db).await?),
+        false => None,
+    };
+    let columns: Vec<E::Column> = keys.iter().map(|(c, _)| *c).collect();
+    let query = build_cursor_query::<E>(req, &keys)?.limit(req.size + 1);
+    let mut rows = projection.select(query, &columns).all(db).await?;
+    let next_cursor = if rows.len() as u64 > req.size {
+        rows.truncate(req.size as usize);
+        rows.last().map(|row| encode_cursor::<E>(&keys, row))
+    } else {
+        None
+    };
+    let data = projection.finish(db, rows).await?;
+    Ok(vo::PageResp::cursor(req.size, data, next_cursor, total))
+}
+
 /// 根据请求条件查询全量数据（不分页）
 ///
 /// # 参数
@@ -87,8 +153,49 @@ where
 /// * `Err(Error)` - 查询过程中发生的错误
 pub async fn query_all<E>(db: &sea_orm::DbConn, req: &vo::QueryReq) -> Result<Vec<E::Model>, Error>
 where
-    E: EntityTrait,
+    E: QueryPolicy,
 {
     let result = build_query::<E>(req)?.all(db).await?;
     Ok(result)
 }
+
+#[cfg(test)]
+mod test_common {
+    use super::*;
+    use crate::entity::user;
+    use sea_orm::{DbBackend, MockDatabase, Value};
+    use std::collections::BTreeMap;
+
+    /// 模拟库返回整行（含 password、salt），响应与查询语句中都不应出现这两列
+    fn user_row() -> BTreeMap<String, Value> {
+        BTreeMap::from([
+            ("id".to_string(), Value::from(1)),
+            ("username".to_string(), Value::from("admin")),
+            ("password".to_string(), Value::from("hash")),
+            ("salt".to_string(), Value::from("salt")),
+            ("role".to_string(), Value::from(0)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_query_by_page_hides_secrets() {
+        let count = BTreeMap::from([("num_items".to_string(), Value::from(1i32))]);
+        // 偏移分页先统计总数，游标分页直接取数据
+        for (json, results) in [
+            (r#"{}"#, vec![vec![count], vec![user_row()]]),
+            (r#"{"mode":"cursor"}"#, vec![vec![user_row()]]),
+        ] {
+            let db = MockDatabase::new(DbBackend::MySql)
+                .append_query_results(results)
+                .into_connection();
+            let req: vo::QueryReq = serde_json::from_str(json).unwrap();
+            let resp = query_by_page::<user::Entity>(&db, &req).await.unwrap();
+            let data = serde_json::to_string(&resp.data).unwrap();
+            assert!(data.contains("\"username\":\"admin\""));
+            assert!(!data.contains("password") && !data.contains("salt"));
+
+            let log = format!("{:?}", db.into_transaction_log());
+            assert!(!log.contains("`password`") && !log.contains("`salt`"));
+        }
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{Error, entity, vo::endpoint_vo};
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait};
+use crate::{
+    Error,
+    dao::{AuditAction, AuditDao},
+    entity,
+    vo::endpoint_vo,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityName, EntityTrait};
 
 pub struct EndpointDao;
 
@@ -15,21 +20,31 @@ impl EndpointDao {
             domain_code: Set(parm.domain_code.clone()),
             path: Set(parm.path.clone()),
             description: Set(parm.description.clone()),
-            is_active: Set(parm.is_active.clone()),
+            is_active: Set(parm.is_active),
             ..Default::default()
         };
-        Ok(ment.insert(db).await?)
+        let created = ment.insert(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Create,
+            entity::endpoint::Entity.table_name(),
+            created.id,
+            None,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
     }
 
     pub async fn update_by_id(
         db: &sea_orm::DbConn,
         parm: &endpoint_vo::UpdateReq,
     ) -> Result<entity::endpoint::Model, Error> {
-        let model = entity::endpoint::Entity::find_by_id(parm.id)
+        let before = entity::endpoint::Entity::find_by_id(parm.id)
             .one(db)
             .await?
             .ok_or_else(|| Error::NotFound(format!("ID {} 不存在", parm.id)))?;
-        let mut model: entity::endpoint::ActiveModel = model.into();
+        let mut model: entity::endpoint::ActiveModel = before.clone().into();
         // 遍历字段，只更新有值的字段
         if let Some(name) = &parm.name {
             model.name = Set(name.clone());
@@ -50,10 +65,19 @@ impl EndpointDao {
             model.description = Set(Some(description.clone()));
         }
         if let Some(is_active) = &parm.is_active {
-            model.is_active = Set(Some(is_active.clone()));
+            model.is_active = Set(Some(*is_active));
         }
 
         let result = model.update(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Update,
+            entity::endpoint::Entity.table_name(),
+            result.id,
+            Some(&before),
+            Some(&result),
+        )
+        .await;
         Ok(result)
     }
 }
@@ -1,5 +1,13 @@
-use crate::{Error, entity, vo::menu_vo};
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityTrait};
+use crate::{
+    Error,
+    dao::{AuditAction, AuditDao},
+    entity,
+    vo::menu_vo,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityName, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
 
 pub struct MenuDao;
 
@@ -20,21 +28,31 @@ impl MenuDao {
             remark: Set(parm.remark.clone()),
             ..Default::default()
         };
-        Ok(ment.insert(db).await?)
+        let created = ment.insert(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Create,
+            entity::menu::Entity.table_name(),
+            created.id,
+            None,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
     }
 
     pub async fn update_by_id(
         db: &sea_orm::DbConn,
         parm: &menu_vo::UpdateReq,
     ) -> Result<entity::menu::Model, Error> {
-        let model = entity::menu::Entity::find_by_id(parm.id)
+        let before = entity::menu::Entity::find_by_id(parm.id)
             .one(db)
             .await?
             .ok_or_else(|| Error::NotFound(format!("ID {} 不存在", parm.id)))?;
-        let mut model: entity::menu::ActiveModel = model.into();
+        let mut model: entity::menu::ActiveModel = before.clone().into();
         // 遍历字段，只更新有值的字段
         if let Some(parent_id) = &parm.parent_id {
-            model.parent_id = Set(parent_id.clone());
+            model.parent_id = Set(*parent_id);
         }
         if let Some(path) = &parm.path {
             model.path = Set(path.clone());
@@ -49,19 +67,47 @@ impl MenuDao {
             model.redirect = Set(Some(redirect.clone()));
         }
         if let Some(sort) = &parm.sort {
-            model.sort = Set(sort.clone());
+            model.sort = Set(*sort);
         }
         if let Some(meta) = &parm.meta {
             model.meta = Set(Some(meta.clone()));
         }
         if let Some(status) = &parm.status {
-            model.status = Set(status.clone());
+            model.status = Set(*status);
         }
         if let Some(remark) = &parm.remark {
             model.remark = Set(Some(remark.clone()));
         }
 
         let result = model.update(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Update,
+            entity::menu::Entity.table_name(),
+            result.id,
+            Some(&before),
+            Some(&result),
+        )
+        .await;
         Ok(result)
     }
+
+    /// 查询全部菜单，按 sort 升序
+    pub async fn query_all_sorted(db: &sea_orm::DbConn) -> Result<Vec<entity::menu::Model>, Error> {
+        Ok(entity::menu::Entity::find()
+            .order_by_asc(entity::menu::Column::Sort)
+            .all(db)
+            .await?)
+    }
+
+    /// 查询角色被授权的菜单 ID
+    pub async fn query_ids_by_role(db: &sea_orm::DbConn, role_id: i32) -> Result<Vec<i64>, Error> {
+        Ok(entity::role_menu::Entity::find()
+            .select_only()
+            .column(entity::role_menu::Column::MenuId)
+            .filter(entity::role_menu::Column::RoleId.eq(role_id))
+            .into_tuple()
+            .all(db)
+            .await?)
+    }
 }
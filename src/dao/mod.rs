@@ -1,10 +1,25 @@
+mod api_key_dao;
+mod audit_dao;
 mod common;
 mod endpoint_dao;
 mod menu_dao;
+mod password_history_dao;
+mod permission_dao;
 mod query_build;
+mod query_expand;
+mod query_policy;
 mod user_dao;
+mod user_identity_dao;
+mod user_totp_dao;
 
+pub use api_key_dao::ApiKeyDao;
+pub use audit_dao::{AuditAction, AuditDao};
 pub use common::*;
 pub use endpoint_dao::EndpointDao;
 pub use menu_dao::MenuDao;
+pub use password_history_dao::PasswordHistoryDao;
+pub use permission_dao::PermissionDao;
+pub use query_policy::QueryPolicy;
 pub use user_dao::UserDao;
+pub use user_identity_dao::UserIdentityDao;
+pub use user_totp_dao::UserTotpDao;
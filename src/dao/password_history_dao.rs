@@ -0,0 +1,63 @@
+use crate::{Error, entity};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+
+pub struct PasswordHistoryDao;
+
+impl PasswordHistoryDao {
+    pub async fn insert(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+        password_hash: &str,
+    ) -> Result<(), Error> {
+        let model = entity::password_history::ActiveModel {
+            user_id: Set(user_id),
+            password: Set(password_hash.to_string()),
+            ..Default::default()
+        };
+        model.insert(db).await?;
+        Ok(())
+    }
+
+    /// 查询最近 limit 次的密码哈希
+    pub async fn query_recent(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+        limit: u64,
+    ) -> Result<Vec<String>, Error> {
+        let hashes: Vec<String> = entity::password_history::Entity::find()
+            .select_only()
+            .column(entity::password_history::Column::Password)
+            .filter(entity::password_history::Column::UserId.eq(user_id))
+            .order_by_desc(entity::password_history::Column::Id)
+            .limit(limit)
+            .into_tuple()
+            .all(db)
+            .await?;
+        Ok(hashes)
+    }
+
+    /// 只保留最近 keep 条记录
+    pub async fn prune(db: &sea_orm::DbConn, user_id: i64, keep: u64) -> Result<(), Error> {
+        // MySQL 不支持单独的 OFFSET，取出全部 ID 后跳过
+        let ids: Vec<i64> = entity::password_history::Entity::find()
+            .select_only()
+            .column(entity::password_history::Column::Id)
+            .filter(entity::password_history::Column::UserId.eq(user_id))
+            .order_by_desc(entity::password_history::Column::Id)
+            .into_tuple()
+            .all(db)
+            .await?;
+        let stale: Vec<i64> = ids.into_iter().skip(keep as usize).collect();
+        if stale.is_empty() {
+            return Ok(());
+        }
+        entity::password_history::Entity::delete_many()
+            .filter(entity::password_history::Column::Id.is_in(stale))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
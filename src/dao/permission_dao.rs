@@ -0,0 +1,32 @@
+use crate::{Error, entity};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+pub struct PermissionDao;
+
+impl PermissionDao {
+    /// 查询角色拥有的全部权限码
+    pub async fn query_codes_by_role(
+        db: &sea_orm::DbConn,
+        role_id: i32,
+    ) -> Result<Vec<String>, Error> {
+        let permission_ids: Vec<i64> = entity::role_permission::Entity::find()
+            .select_only()
+            .column(entity::role_permission::Column::PermissionId)
+            .filter(entity::role_permission::Column::RoleId.eq(role_id))
+            .into_tuple()
+            .all(db)
+            .await?;
+        if permission_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let codes: Vec<String> = entity::permission::Entity::find()
+            .select_only()
+            .column(entity::permission::Column::Code)
+            .filter(entity::permission::Column::Id.is_in(permission_ids))
+            .into_tuple()
+            .all(db)
+            .await?;
+        Ok(codes)
+    }
+}
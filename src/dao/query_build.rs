@@ -1,16 +1,24 @@
-use sea_orm::{Condition, EntityTrait, ExprTrait, QueryFilter, QueryOrder, prelude::Expr};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use sea_orm::{
+    ColumnTrait, ColumnType, Condition, ExprTrait, IdenStatic, Iterable, PrimaryKeyToColumn,
+    QueryFilter, QueryOrder, Value, prelude::Expr,
+};
+use serde_json::Value as Json;
 use std::str::FromStr;
 
-use crate::{error::Error, vo};
+use crate::{core::config::CONFIG, dao::QueryPolicy, error::Error, vo};
 
 // ============================
 //  复杂查询逻辑构建（条件、排序等）
 // ============================
 
 /// 根据 SearchReq 构造查询，失败则返回错误
+///
+/// 过滤与排序字段须在实体的 [`QueryPolicy`] 白名单内
 pub fn build_query<E>(req: &vo::QueryReq) -> Result<sea_orm::Select<E>, Error>
 where
-    E: EntityTrait,
+    E: QueryPolicy,
 {
     let mut query = E::find();
 
@@ -24,13 +32,8 @@ where
     if let Some(sorts) = &req.sorts {
         for sort in sorts {
             let column = match E::Column::from_str(&sort.field) {
-                Ok(c) => c,
-                Err(_) => {
-                    return Err(Error::BuildQueryError(format!(
-                        "排序字段 {} 不存在",
-                        sort.field
-                    )));
-                }
+                Ok(c) if E::can_sort(&c) => c,
+                _ => return Err(Error::InvalidQueryField(sort.field.clone())),
             };
 
             match sort.order {
@@ -43,81 +46,339 @@ where
     Ok(query)
 }
 
-/// 构造 SeaORM 的 Condition
+/// 游标分页的排序键：请求的排序字段，再补上主键，保证顺序唯一
+pub fn cursor_keys<E>(req: &vo::QueryReq) -> Result<Vec<(E::Column, vo::Order)>, Error>
+where
+    E: QueryPolicy,
+{
+    let mut keys = Vec::new();
+    for sort in req.sorts.iter().flatten() {
+        let column = match E::Column::from_str(&sort.field) {
+            Ok(c) if E::can_sort(&c) => c,
+            _ => return Err(Error::InvalidQueryField(sort.field.clone())),
+        };
+        // NULL 无法参与 keyset 比较
+        if column.def().is_null() {
+            return Err(Error::BuildQueryError(format!(
+                "游标分页不能按可空字段 {} 排序",
+                sort.field
+            )));
+        }
+        keys.push((column, sort.order.clone()));
+    }
+    for key in E::PrimaryKey::iter() {
+        let column = key.into_column();
+        if !keys.iter().any(|(c, _)| c.as_str() == column.as_str()) {
+            keys.push((column, vo::Order::Asc));
+        }
+    }
+    Ok(keys)
+}
+
+/// 构造游标分页查询：在 build_query 基础上补齐主键排序，并按游标定位到上一页之后
+pub fn build_cursor_query<E>(
+    req: &vo::QueryReq,
+    keys: &[(E::Column, vo::Order)],
+) -> Result<sea_orm::Select<E>, Error>
+where
+    E: QueryPolicy,
+{
+    let mut query = build_query::<E>(req)?;
+    let sorted = req.sorts.as_ref().map_or(0, Vec::len);
+    for (column, order) in keys.iter().skip(sorted) {
+        query = match order {
+            vo::Order::Asc => query.order_by_asc(*column),
+            vo::Order::Desc => query.order_by_desc(*column),
+        };
+    }
+    if let Some(cursor) = &req.cursor {
+        query = query.filter(keyset_condition::<E>(keys, cursor)?);
+    }
+    Ok(query)
+}
+
+/// (k1, k2, ...) 位于游标之后：k1 > v1 OR (k1 = v1 AND k2 > v2) OR ...，降序时取 <
+fn keyset_condition<E>(keys: &[(E::Column, vo::Order)], cursor: &str) -> Result<Condition, Error>
+where
+    E: QueryPolicy,
+{
+    let invalid = || Error::BuildQueryError("分页游标无效".to_string());
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let entries: Vec<(String, Json)> = serde_json::from_slice(&bytes).map_err(|_| invalid())?;
+    // 游标须与本次排序键一致，排序变化后旧游标失效
+    if entries.len() != keys.len()
+        || keys
+            .iter()
+            .zip(&entries)
+            .any(|((column, _), (name, _))| column.as_str() != name)
+    {
+        return Err(invalid());
+    }
+    let values = keys
+        .iter()
+        .zip(&entries)
+        .map(|((column, _), (name, value))| coerce(name, column.def().get_column_type(), value))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut cond = Condition::any();
+    for (i, (column, order)) in keys.iter().enumerate() {
+        let mut branch = Condition::all();
+        for (prev, value) in keys.iter().zip(&values).take(i) {
+            branch = branch.add(Expr::col(prev.0).eq(value.clone()));
+        }
+        branch = branch.add(match order {
+            vo::Order::Asc => Expr::col(*column).gt(values[i].clone()),
+            vo::Order::Desc => Expr::col(*column).lt(values[i].clone()),
+        });
+        cond = cond.add(branch);
+    }
+    Ok(cond)
+}
+
+/// 以当前页最后一行的排序键生成下一页游标，行为按列名为键的查询结果
+pub fn encode_cursor<E>(keys: &[(E::Column, vo::Order)], row: &Json) -> String
+where
+    E: QueryPolicy,
+{
+    let entries: Vec<(&str, &Json)> = keys
+        .iter()
+        .map(|(column, _)| {
+            (
+                column.as_str(),
+                row.get(column.as_str()).unwrap_or(&Json::Null),
+            )
+        })
+        .collect();
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(&entries).unwrap_or_default())
+}
+
+/// 构造 SeaORM 的 Condition，分组递归生成嵌套 Condition
+///
+/// 嵌套层数与节点总数受 `[query]` 配置限制，避免构造代价过高的查询
 fn build_condition<E>(
-    filters: &[vo::Filter],
+    filters: &[vo::FilterNode],
+    logic: &vo::LogicOp,
+) -> Result<sea_orm::Condition, Error>
+where
+    E: QueryPolicy,
+{
+    let mut nodes = 0;
+    build_group::<E>(filters, logic, 1, &mut nodes)
+}
+
+fn build_group<E>(
+    children: &[vo::FilterNode],
     logic: &vo::LogicOp,
+    depth: usize,
+    nodes: &mut usize,
 ) -> Result<sea_orm::Condition, Error>
 where
-    E: EntityTrait,
+    E: QueryPolicy,
 {
+    let limit = &CONFIG.query;
+    if depth > limit.max_depth {
+        return Err(Error::BuildQueryError(format!(
+            "过滤条件嵌套过深，最多 {} 层",
+            limit.max_depth
+        )));
+    }
+
     let mut cond = match logic {
         vo::LogicOp::And => Condition::all(),
         vo::LogicOp::Or => Condition::any(),
     };
 
-    for filter in filters {
-        // 获取 column（安全地防注入）
-        let column = match E::Column::from_str(&filter.field) {
-            Ok(c) => c,
-            Err(_) => {
-                return Err(Error::InvalidQueryField(format!("{}", filter.field)));
-            }
+    for child in children {
+        *nodes += 1;
+        if *nodes > limit.max_nodes {
+            return Err(Error::BuildQueryError(format!(
+                "过滤条件过多，最多 {} 个",
+                limit.max_nodes
+            )));
+        }
+
+        cond = match child {
+            vo::FilterNode::Filter(filter) => add_filter::<E>(cond, filter)?,
+            vo::FilterNode::Group(group) => cond.add(build_group::<E>(
+                &group.children,
+                &group.logic,
+                depth + 1,
+                nodes,
+            )?),
         };
+    }
 
-        let field = Expr::col(column);
+    Ok(cond)
+}
 
-        match filter.op {
-            vo::CompareOp::Eq => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.eq(v.clone()));
-                }
-            }
-            vo::CompareOp::Ne => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.ne(v.clone()));
-                }
-            }
-            vo::CompareOp::Gt => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.gt(v.clone()));
-                }
-            }
-            vo::CompareOp::Ge => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.gte(v.clone()));
-                }
-            }
-            vo::CompareOp::Lt => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.lt(v.clone()));
-                }
-            }
-            vo::CompareOp::Le => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.lte(v.clone()));
-                }
-            }
-            vo::CompareOp::Like => {
-                if let Some(v) = filter.values.first() {
-                    cond = cond.add(field.like(format!("%{}%", v)));
-                }
+/// 将单个过滤条件追加到 Condition
+fn add_filter<E>(cond: Condition, filter: &vo::Filter) -> Result<Condition, Error>
+where
+    E: QueryPolicy,
+{
+    // 获取 column（安全地防注入）
+    let column = match E::Column::from_str(&filter.field) {
+        Ok(c) if E::can_filter(&c) => c,
+        _ => return Err(Error::InvalidQueryField(filter.field.clone())),
+    };
+
+    let column_type = column.def().get_column_type().clone();
+    let field = Expr::col(column);
+    let typed = |v: &Json| match v {
+        Json::Null => Err(Error::BuildQueryError(format!(
+            "字段 {} 的比较值不能为 null",
+            filter.field
+        ))),
+        _ => coerce(&filter.field, &column_type, v),
+    };
+    let first = || {
+        filter
+            .values
+            .first()
+            .ok_or_else(|| Error::BuildQueryError(format!("字段 {} 缺少比较值", filter.field)))
+    };
+
+    let expr = match filter.op {
+        vo::CompareOp::Eq => match first()? {
+            Json::Null => field.is_null(),
+            v => field.eq(typed(v)?),
+        },
+        vo::CompareOp::Ne => match first()? {
+            Json::Null => field.is_not_null(),
+            v => field.ne(typed(v)?),
+        },
+        vo::CompareOp::Gt => field.gt(typed(first()?)?),
+        vo::CompareOp::Ge => field.gte(typed(first()?)?),
+        vo::CompareOp::Lt => field.lt(typed(first()?)?),
+        vo::CompareOp::Le => field.lte(typed(first()?)?),
+        vo::CompareOp::Like => {
+            let text = match first()? {
+                Json::String(s) => s.clone(),
+                v @ (Json::Number(_) | Json::Bool(_)) => v.to_string(),
+                v => return Err(invalid_value(&filter.field, v, "字符串")),
+            };
+            field.like(format!("%{}%", text))
+        }
+        vo::CompareOp::In => {
+            // null 不能参与 IN 比较，单独转为 IS NULL
+            let (nulls, values): (Vec<&Json>, Vec<&Json>) =
+                filter.values.iter().partition(|v| v.is_null());
+            let values = values
+                .into_iter()
+                .map(typed)
+                .collect::<Result<Vec<_>, _>>()?;
+            let expr = field.clone().is_in(values);
+            if nulls.is_empty() {
+                expr
+            } else {
+                expr.or(field.is_null())
             }
-            vo::CompareOp::In => {
-                cond = cond.add(field.is_in(filter.values.clone()));
+        }
+        vo::CompareOp::Between => match filter.values.as_slice() {
+            [from, to] => field.between(typed(from)?, typed(to)?),
+            _ => {
+                return Err(Error::BuildQueryError(format!(
+                    "字段 {} 的区间条件需要 2 个值",
+                    filter.field
+                )));
             }
-            vo::CompareOp::Between => {
-                if filter.values.len() == 2 {
-                    cond =
-                        cond.add(field.between(filter.values[0].clone(), filter.values[1].clone()));
-                }
+        },
+        vo::CompareOp::IsNull => field.is_null(),
+        vo::CompareOp::IsNotNull => field.is_not_null(),
+    };
+
+    Ok(cond.add(expr))
+}
+
+/// 按列类型将过滤值转换为对应类型的 Value，不依赖数据库的隐式转换
+///
+/// 值可为 JSON 字符串、数字或布尔；时间类型接受 `2024-01-02 03:04:05`、
+/// ISO 8601（`T` 分隔，可带时区）及仅日期的写法，按本地时间比较
+fn coerce(field: &str, column_type: &ColumnType, value: &Json) -> Result<Value, Error> {
+    let invalid = |expect: &str| invalid_value(field, value, expect);
+    let text = match value {
+        Json::String(s) => s.trim().to_string(),
+        Json::Number(n) => n.to_string(),
+        Json::Bool(b) => b.to_string(),
+        _ => return Err(invalid("标量")),
+    };
+
+    let value = match column_type {
+        ColumnType::TinyInteger
+        | ColumnType::SmallInteger
+        | ColumnType::Integer
+        | ColumnType::BigInteger => match value {
+            Json::Bool(b) => Value::from(*b as i64),
+            _ => Value::from(text.parse::<i64>().map_err(|_| invalid("整数"))?),
+        },
+        ColumnType::TinyUnsigned
+        | ColumnType::SmallUnsigned
+        | ColumnType::Unsigned
+        | ColumnType::BigUnsigned => match value {
+            Json::Bool(b) => Value::from(*b as u64),
+            _ => Value::from(text.parse::<u64>().map_err(|_| invalid("非负整数"))?),
+        },
+        ColumnType::Float | ColumnType::Double | ColumnType::Decimal(_) | ColumnType::Money(_) => {
+            let n = text.parse::<f64>().map_err(|_| invalid("数值"))?;
+            if !n.is_finite() {
+                return Err(invalid("数值"));
             }
-            vo::CompareOp::IsNull => cond = cond.add(field.is_null()),
-            vo::CompareOp::IsNotNull => cond = cond.add(field.is_not_null()),
-        };
+            Value::from(n)
+        }
+        ColumnType::Boolean => match text.as_str() {
+            "true" | "1" => Value::from(true),
+            "false" | "0" => Value::from(false),
+            _ => return Err(invalid("布尔值")),
+        },
+        ColumnType::DateTime | ColumnType::Timestamp => {
+            Value::from(parse_datetime(&text).ok_or_else(|| invalid("日期时间"))?)
+        }
+        ColumnType::TimestampWithTimeZone => {
+            let local = parse_datetime(&text).ok_or_else(|| invalid("日期时间"))?;
+            let utc = local
+                .and_local_timezone(Local)
+                .earliest()
+                .ok_or_else(|| invalid("日期时间"))?
+                .with_timezone(&Utc);
+            Value::from(utc)
+        }
+        ColumnType::Date => {
+            Value::from(NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|_| invalid("日期"))?)
+        }
+        ColumnType::Time => Value::from(
+            NaiveTime::parse_from_str(&text, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(&text, "%H:%M"))
+                .map_err(|_| invalid("时间"))?,
+        ),
+        _ => Value::from(text),
+    };
+    Ok(value)
+}
+
+/// 解析为本地时间；带时区的写法先换算到本地时区
+fn parse_datetime(text: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.with_timezone(&Local).naive_local());
     }
+    [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|fmt| NaiveDateTime::parse_from_str(text, fmt).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+    })
+}
 
-    Ok(cond)
+fn invalid_value(field: &str, value: &Json, expect: &str) -> Error {
+    Error::BuildQueryError(format!(
+        "字段 {} 的值 {} 无效，应为{}",
+        field, value, expect
+    ))
 }
 
 #[cfg(test)]
@@ -125,22 +386,14 @@ mod test_query {
 
     use super::*;
     use crate::entity;
-    use sea_orm::{DbBackend, QueryTrait};
+    use sea_orm::{DbBackend, EntityTrait, QueryTrait};
 
     #[test]
     fn test_build_sql() {
         let req = vo::QueryReq {
             filters: Some(vec![
-                vo::Filter {
-                    field: "name".to_string(),
-                    op: vo::CompareOp::Like,
-                    values: vec!["Alice".to_string()],
-                },
-                vo::Filter {
-                    field: "sort".to_string(),
-                    op: vo::CompareOp::Gt,
-                    values: vec!["20".to_string()],
-                },
+                filter("name", vo::CompareOp::Like, "Alice"),
+                filter("sort", vo::CompareOp::Gt, "20"),
             ]),
             logic: vo::LogicOp::And,
             sorts: Some(vec![vo::SortField {
@@ -149,10 +402,168 @@ mod test_query {
             }]),
             page: 1,
             size: 50,
+            mode: vo::PageMode::Offset,
+            cursor: None,
+            with_total: false,
+            fields: None,
+            expand: None,
         };
 
         let query = build_query::<entity::menu::Entity>(&req).unwrap();
         let stmt = query.build(DbBackend::MySql);
-        println!("Generated SQL: {}", stmt.to_string());
+        println!("Generated SQL: {}", stmt);
+    }
+
+    fn filter(field: &str, op: vo::CompareOp, value: &str) -> vo::FilterNode {
+        vo::FilterNode::Filter(vo::Filter {
+            field: field.to_string(),
+            op,
+            values: vec![value.into()],
+        })
+    }
+
+    fn group(logic: vo::LogicOp, children: Vec<vo::FilterNode>) -> vo::FilterNode {
+        vo::FilterNode::Group(vo::FilterGroup { logic, children })
+    }
+
+    #[test]
+    fn test_nested_group() {
+        let nodes = vec![
+            filter("name", vo::CompareOp::Like, "Alice"),
+            group(
+                vo::LogicOp::Or,
+                vec![
+                    filter("status", vo::CompareOp::Eq, "1"),
+                    filter("sort", vo::CompareOp::Gt, "10"),
+                ],
+            ),
+        ];
+        let cond = build_condition::<entity::menu::Entity>(&nodes, &vo::LogicOp::And).unwrap();
+        let sql = entity::menu::Entity::find()
+            .filter(cond)
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.ends_with("WHERE `name` LIKE '%Alice%' AND (`status` = 1 OR `sort` > 10)"));
+    }
+
+    #[test]
+    fn test_group_limits() {
+        let limit = &CONFIG.query;
+        let mut node = filter("status", vo::CompareOp::Eq, "1");
+        for _ in 0..limit.max_depth {
+            node = group(vo::LogicOp::And, vec![node]);
+        }
+        let res = build_condition::<entity::menu::Entity>(&[node], &vo::LogicOp::And);
+        assert!(matches!(res, Err(Error::BuildQueryError(_))));
+
+        let nodes: Vec<_> = (0..=limit.max_nodes)
+            .map(|_| filter("status", vo::CompareOp::Eq, "1"))
+            .collect();
+        let res = build_condition::<entity::menu::Entity>(&nodes, &vo::LogicOp::And);
+        assert!(matches!(res, Err(Error::BuildQueryError(_))));
+    }
+
+    #[test]
+    fn test_parse_nodes() {
+        let req: vo::QueryReq = serde_json::from_str(
+            r#"{"filters":[{"field":"name","op":"eq","values":["a"]},{"logic":"or","children":[{"field":"sort","op":"gt","values":["1"]}]}]}"#,
+        )
+        .unwrap();
+        let filters = req.filters.unwrap();
+        assert!(matches!(filters[0], vo::FilterNode::Filter(_)));
+        assert!(matches!(filters[1], vo::FilterNode::Group(_)));
+    }
+
+    #[test]
+    fn test_policy_rejects_column() {
+        let mut req: vo::QueryReq =
+            serde_json::from_str(r#"{"filters":[{"field":"password","op":"eq","values":["x"]}]}"#)
+                .unwrap();
+        let res = build_query::<entity::user::Entity>(&req);
+        assert!(matches!(res, Err(Error::InvalidQueryField(f)) if f == "password"));
+
+        req.filters = None;
+        req.sorts = Some(vec![vo::SortField {
+            field: "salt".to_string(),
+            order: vo::Order::Asc,
+        }]);
+        let res = build_query::<entity::user::Entity>(&req);
+        assert!(matches!(res, Err(Error::InvalidQueryField(f)) if f == "salt"));
+    }
+
+    fn where_sql(filters: &str) -> Result<String, Error> {
+        let req: vo::QueryReq =
+            serde_json::from_str(&format!(r#"{{"filters":{}}}"#, filters)).unwrap();
+        let query = build_query::<entity::user::Entity>(&req)?;
+        let sql = query.build(DbBackend::MySql).to_string();
+        Ok(sql[sql.find("WHERE").unwrap()..].to_string())
+    }
+
+    #[test]
+    fn test_coerce_values() {
+        assert_eq!(
+            where_sql(r#"[{"field":"role","op":"gt","values":[1]}]"#).unwrap(),
+            "WHERE `role` > 1"
+        );
+        assert_eq!(
+            where_sql(r#"[{"field":"enableFlag","op":"eq","values":[true]}]"#).unwrap(),
+            "WHERE `enable_flag` = 1"
+        );
+        assert_eq!(
+            where_sql(r#"[{"field":"locale","op":"in","values":["en-US",null]}]"#).unwrap(),
+            "WHERE `locale` IN ('en-US') OR `locale` IS NULL"
+        );
+        assert_eq!(
+            where_sql(
+                r#"[{"field":"createdAt","op":"between","values":["2024-01-01","2024-01-31T12:30:00"]}]"#
+            )
+            .unwrap(),
+            "WHERE `created_at` BETWEEN '2024-01-01 00:00:00.000000' AND '2024-01-31 12:30:00.000000'"
+        );
+        assert_eq!(
+            where_sql(r#"[{"field":"locale","op":"eq","values":[null]}]"#).unwrap(),
+            "WHERE `locale` IS NULL"
+        );
+
+        let err = where_sql(r#"[{"field":"role","op":"gt","values":["abc"]}]"#).unwrap_err();
+        assert!(
+            matches!(err, Error::BuildQueryError(msg) if msg == "字段 role 的值 \"abc\" 无效，应为整数")
+        );
+        let err = where_sql(r#"[{"field":"id","op":"between","values":[1]}]"#).unwrap_err();
+        assert!(matches!(err, Error::BuildQueryError(_)));
+    }
+
+    #[test]
+    fn test_cursor_keyset() {
+        let mut req: vo::QueryReq =
+            serde_json::from_str(r#"{"mode":"cursor","sorts":[{"field":"sort","order":"desc"}]}"#)
+                .unwrap();
+        let keys = cursor_keys::<entity::menu::Entity>(&req).unwrap();
+        assert_eq!(keys.len(), 2);
+
+        // 查询结果以列名为键
+        let last = serde_json::json!({ "id": 3, "sort": 5, "name": "a" });
+        req.cursor = Some(encode_cursor::<entity::menu::Entity>(&keys, &last));
+        let sql = build_cursor_query::<entity::menu::Entity>(&req, &keys)
+            .unwrap()
+            .build(DbBackend::MySql)
+            .to_string();
+        assert!(sql.ends_with(
+            "WHERE `sort` < 5 OR (`sort` = 5 AND `id` > 3) ORDER BY `menu`.`sort` DESC, `menu`.`id` ASC"
+        ));
+
+        // 排序变化后旧游标失效
+        req.sorts = None;
+        let keys = cursor_keys::<entity::menu::Entity>(&req).unwrap();
+        let res = build_cursor_query::<entity::menu::Entity>(&req, &keys);
+        assert!(matches!(res, Err(Error::BuildQueryError(_))));
+
+        // 可空字段不能作为游标排序键
+        req.sorts = Some(vec![vo::SortField {
+            field: "createdAt".to_string(),
+            order: vo::Order::Asc,
+        }]);
+        let res = cursor_keys::<entity::user::Entity>(&req);
+        assert!(matches!(res, Err(Error::BuildQueryError(_))));
     }
 }
@@ -0,0 +1,371 @@
+//! 通用查询的列投影与关联展开
+//!
+//! 查询结果以 JSON 对象返回：只查询请求的列（未指定时为全部可返回列），
+//! 并按实体声明的 sea-orm 关联批量加载关联数据，每个关联一次查询。
+//! 展开项可指定关联的返回列，如 `details(id,contentType)`；
+//! 一对多关联每行最多返回 `MAX_EXPAND_ROWS` 条（依赖窗口函数 ROW_NUMBER）。
+
+use std::{str::FromStr, sync::LazyLock};
+
+use heck::ToLowerCamelCase;
+use sea_orm::{
+    ConnectionTrait, FromQueryResult, IdenStatic, Identity, Iterable, JsonValue,
+    PrimaryKeyToColumn, QuerySelect, QueryTrait, Related, RelationDef, RelationType, Select,
+    SelectModel, Selector, Value,
+    prelude::Expr,
+    sea_query::{Alias, Asterisk, Order, Query, SelectStatement, WindowStatement},
+};
+
+use crate::{core::constant::MAX_EXPAND_ROWS, dao::QueryPolicy, error::Error, vo};
+
+/// 实体声明的关联，首次使用时生成并缓存
+pub type Expansions = LazyLock<Result<Vec<Expansion>, String>>;
+
+/// 取出已缓存的关联声明
+pub fn declared(expansions: &'static Expansions) -> Result<&'static [Expansion], Error> {
+    match &**expansions {
+        Ok(expansions) => Ok(expansions),
+        Err(e) => Err(Error::BuildQueryError(e.clone())),
+    }
+}
+
+/// 可展开的关联
+pub struct Expansion {
+    name: &'static str,
+    many: bool,
+    /// 主实体上的关联列
+    from_col: String,
+    /// 关联实体上的关联列
+    to_col: String,
+    /// 关联实体的主键列，一对多时按其排序截取
+    order_col: String,
+    /// 关联实体可展开的列
+    columns: Vec<&'static str>,
+    /// 仅含 FROM 的基础查询，返回列按请求追加
+    query: SelectStatement,
+}
+
+impl Expansion {
+    /// 由 sea-orm 关联定义生成，关联数据仅返回关联实体的可展开列
+    pub fn of<E, R>(name: &'static str) -> Result<Self, String>
+    where
+        E: Related<R>,
+        R: QueryPolicy,
+    {
+        let rel: RelationDef = <E as Related<R>>::to();
+        let order_col = R::PrimaryKey::iter()
+            .map(|key| key.into_column().as_str().to_string())
+            .next()
+            .ok_or_else(|| format!("关联实体缺少主键: {}", name))?;
+        Ok(Self {
+            name,
+            many: rel.rel_type == RelationType::HasMany,
+            from_col: column_name(&rel.from_col)?,
+            to_col: column_name(&rel.to_col)?,
+            order_col,
+            columns: R::EXPANDABLE.iter().map(|c| c.as_str()).collect(),
+            query: R::find().select_only().into_query(),
+        })
+    }
+
+    /// 解析展开项中的返回列，未指定时为全部可展开列
+    fn columns(&self, fields: Option<&str>) -> Result<Vec<&'static str>, Error> {
+        let Some(fields) = fields else {
+            return Ok(self.columns.clone());
+        };
+        let mut columns = Vec::new();
+        for field in fields.split(',').map(str::trim) {
+            match self
+                .columns
+                .iter()
+                .find(|c| **c == field || c.to_lower_camel_case() == field)
+            {
+                Some(c) if !columns.contains(c) => columns.push(*c),
+                _ => return Err(Error::InvalidQueryField(format!("{}.{}", self.name, field))),
+            }
+        }
+        Ok(columns)
+    }
+
+    /// 按关联键批量查询；一对多时每个键最多取 MAX_EXPAND_ROWS 行
+    fn statement(&self, columns: &[&'static str], keys: Vec<Value>) -> SelectStatement {
+        let mut query = self.query.clone();
+        query.columns(columns.iter().map(|c| Alias::new(*c)));
+        // 关联列用于分组，未请求时仍需查询
+        if !columns.contains(&self.to_col.as_str()) {
+            query.column(Alias::new(self.to_col.clone()));
+        }
+        query.and_where(sea_orm::ExprTrait::is_in(
+            Expr::col(Alias::new(self.to_col.clone())),
+            keys,
+        ));
+        if !self.many {
+            return query;
+        }
+        let mut window = WindowStatement::partition_by(Alias::new(self.to_col.clone()));
+        window.order_by(Alias::new(self.order_col.clone()), Order::Asc);
+        query.expr_window_as(Expr::cust("ROW_NUMBER()"), window, Alias::new("expand_row"));
+        Query::select()
+            .column(Asterisk)
+            .from_subquery(query, Alias::new("expand"))
+            .and_where(sea_orm::ExprTrait::lte(
+                Expr::col(Alias::new("expand_row")),
+                MAX_EXPAND_ROWS,
+            ))
+            .to_owned()
+    }
+
+    /// 批量加载关联数据并写入各行，一对一为对象（无则为 null），一对多为数组
+    async fn load<C>(
+        &self,
+        db: &C,
+        columns: &[&'static str],
+        rows: &mut [JsonValue],
+    ) -> Result<(), Error>
+    where
+        C: ConnectionTrait,
+    {
+        let mut keys: Vec<&JsonValue> = Vec::new();
+        for key in rows.iter().filter_map(|row| row.get(&self.from_col)) {
+            if !key.is_null() && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        let related = if keys.is_empty() {
+            Vec::new()
+        } else {
+            let keys = keys.into_iter().filter_map(key_value).collect();
+            let stmt = db
+                .get_database_backend()
+                .build(&self.statement(columns, keys));
+            JsonValue::find_by_statement(stmt).all(db).await?
+        };
+
+        for row in rows.iter_mut() {
+            let key = row.get(&self.from_col).cloned().unwrap_or_default();
+            let mut matched = related
+                .iter()
+                .filter(|r| !key.is_null() && r.get(&self.to_col) == Some(&key))
+                .map(|r| shape(r, columns));
+            let value = match self.many {
+                true => JsonValue::Array(matched.collect()),
+                false => matched.next().unwrap_or_default(),
+            };
+            if let Some(obj) = row.as_object_mut() {
+                obj.insert(self.name.to_string(), value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 一次请求展开的关联及其返回列
+struct Expand {
+    expansion: &'static Expansion,
+    columns: Vec<&'static str>,
+}
+
+/// 一次查询的返回列与展开的关联
+pub struct Projection<E: QueryPolicy> {
+    fields: Vec<E::Column>,
+    expansions: Vec<Expand>,
+}
+
+impl<E: QueryPolicy> Projection<E> {
+    /// 校验 fields 与 expand：字段须可返回，关联须已声明，关联的返回列须可展开
+    pub fn new(req: &vo::QueryReq) -> Result<Self, Error> {
+        let fields = match &req.fields {
+            Some(fields) => fields
+                .iter()
+                .map(|field| match E::Column::from_str(field) {
+                    Ok(c) if E::can_return(&c) => Ok(c),
+                    _ => Err(Error::InvalidQueryField(field.clone())),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => E::RETURNABLE.to_vec(),
+        };
+
+        let declared = E::expansions()?;
+        let mut expansions: Vec<Expand> = Vec::new();
+        for item in req.expand.iter().flatten() {
+            let (name, fields) = match item.split_once('(') {
+                Some((name, rest)) => match rest.strip_suffix(')') {
+                    Some(fields) => (name.trim(), Some(fields)),
+                    None => return Err(Error::InvalidQueryField(item.clone())),
+                },
+                None => (item.trim(), None),
+            };
+            match declared.iter().find(|e| e.name == name) {
+                Some(expansion) if !expansions.iter().any(|x| x.expansion.name == name) => {
+                    let columns = expansion.columns(fields)?;
+                    expansions.push(Expand { expansion, columns });
+                }
+                // 未声明或重复的关联
+                _ => return Err(Error::InvalidQueryField(item.clone())),
+            }
+        }
+        Ok(Self { fields, expansions })
+    }
+
+    /// 只查询返回列、关联列及调用方额外需要的列（如游标排序键）
+    pub fn select(
+        &self,
+        query: Select<E>,
+        extra: &[E::Column],
+    ) -> Selector<SelectModel<JsonValue>> {
+        let mut columns = self.fields.clone();
+        let keys = self
+            .expansions
+            .iter()
+            .filter_map(|e| E::Column::from_str(&e.expansion.from_col).ok());
+        for column in extra.iter().copied().chain(keys) {
+            if !columns.iter().any(|c| c.as_str() == column.as_str()) {
+                columns.push(column);
+            }
+        }
+        query.select_only().columns(columns).into_json()
+    }
+
+    /// 加载关联并整理为响应对象：键为 camelCase，仅保留请求的列与关联
+    pub async fn finish<C>(&self, db: &C, mut rows: Vec<JsonValue>) -> Result<Vec<JsonValue>, Error>
+    where
+        C: ConnectionTrait,
+    {
+        for Expand { expansion, columns } in &self.expansions {
+            expansion.load(db, columns, &mut rows).await?;
+        }
+        let fields: Vec<&str> = self.fields.iter().map(|c| c.as_str()).collect();
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let mut value = shape(row, &fields);
+                if let Some(obj) = value.as_object_mut() {
+                    for Expand { expansion, .. } in &self.expansions {
+                        let related = row.get(expansion.name).cloned().unwrap_or_default();
+                        obj.insert(expansion.name.to_string(), related);
+                    }
+                }
+                value
+            })
+            .collect())
+    }
+}
+
+/// 按列名取值，键转为 camelCase
+fn shape(row: &JsonValue, columns: &[&str]) -> JsonValue {
+    let obj = columns
+        .iter()
+        .map(|c| {
+            let value = row.get(*c).cloned().unwrap_or_default();
+            (c.to_lower_camel_case(), value)
+        })
+        .collect();
+    JsonValue::Object(obj)
+}
+
+/// 关联只支持单列键
+fn column_name(identity: &Identity) -> Result<String, String> {
+    match identity {
+        Identity::Unary(column) => Ok(column.to_string()),
+        _ => Err(format!("关联展开不支持复合键: {:?}", identity)),
+    }
+}
+
+fn key_value(key: &JsonValue) -> Option<Value> {
+    match key {
+        JsonValue::Number(n) => n
+            .as_i64()
+            .map(Value::from)
+            .or_else(|| n.as_u64().map(Value::from)),
+        JsonValue::String(s) => Some(Value::from(s.clone())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_query_expand {
+    use super::*;
+    use crate::entity::{report, task, user};
+    use sea_orm::{DbBackend, EntityTrait};
+
+    fn req(json: &str) -> vo::QueryReq {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_relation_keys() {
+        let to_task = Expansion::of::<report::Entity, task::Entity>("task").unwrap();
+        assert_eq!(
+            (to_task.from_col.as_str(), to_task.to_col.as_str()),
+            ("task_id", "id")
+        );
+        assert!(!to_task.many);
+
+        let to_reports = Expansion::of::<task::Entity, report::Entity>("reports").unwrap();
+        assert_eq!(
+            (to_reports.from_col.as_str(), to_reports.to_col.as_str()),
+            ("id", "task_id")
+        );
+        assert!(to_reports.many);
+    }
+
+    #[test]
+    fn test_projection() {
+        let projection =
+            Projection::<report::Entity>::new(&req(r#"{"fields":["status"],"expand":["task"]}"#))
+                .unwrap();
+        let sql = projection
+            .select(report::Entity::find(), &[])
+            .into_statement(DbBackend::MySql)
+            .to_string();
+        // 展开 task 需要 task_id，即使未请求该列
+        assert!(sql.starts_with("SELECT `report`.`status`, `report`.`task_id` FROM `report`"));
+
+        let row = serde_json::json!({ "status": 1, "task_id": 7, "task": { "id": 7 } });
+        let fields: Vec<&str> = projection.fields.iter().map(|c| c.as_str()).collect();
+        assert_eq!(shape(&row, &fields), serde_json::json!({ "status": 1 }));
+
+        let res = Projection::<user::Entity>::new(&req(r#"{"fields":["password"]}"#));
+        assert!(matches!(res, Err(Error::InvalidQueryField(f)) if f == "password"));
+        let res = Projection::<report::Entity>::new(&req(r#"{"expand":["task","task"]}"#));
+        assert!(matches!(res, Err(Error::InvalidQueryField(f)) if f == "task"));
+    }
+
+    #[test]
+    fn test_expand_columns() {
+        let projection = Projection::<report::Entity>::new(&req(
+            r#"{"expand":["details(id,contentType)","task"]}"#,
+        ))
+        .unwrap();
+        let details = &projection.expansions[0];
+        assert_eq!(details.columns, ["id", "content_type"]);
+        let sql = DbBackend::MySql
+            .build(
+                &details
+                    .expansion
+                    .statement(&details.columns, vec![Value::from(7)]),
+            )
+            .to_string();
+        // 一对多按关联键分组截取，每组最多 MAX_EXPAND_ROWS 行
+        assert_eq!(
+            sql,
+            "SELECT * FROM (SELECT `id`, `content_type`, `report_id`, ROW_NUMBER() OVER ( PARTITION BY `report_id` ORDER BY `id` ASC ) AS `expand_row` FROM `report_detail` WHERE `report_id` IN (7)) AS `expand` WHERE `expand_row` <= 50"
+        );
+
+        // 未指定返回列时为全部可展开列，明细内容不随展开返回
+        let projection =
+            Projection::<report::Entity>::new(&req(r#"{"expand":["details"]}"#)).unwrap();
+        assert!(!projection.expansions[0].columns.contains(&"content"));
+
+        for (expand, field) in [
+            (r#"["details(content)"]"#, "details.content"),
+            (r#"["details(id,id)"]"#, "details.id"),
+            (r#"["details(id"]"#, "details(id"),
+        ] {
+            let res =
+                Projection::<report::Entity>::new(&req(&format!(r#"{{"expand":{}}}"#, expand)));
+            assert!(matches!(res, Err(Error::InvalidQueryField(f)) if f == field));
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! 通用查询的列白名单
+//!
+//! 每个开放动态查询的实体需声明可过滤、可排序、可返回的列，
+//! 未声明的列（如 `user.password`）在查询条件、排序及响应中一律拒绝或剔除；
+//! 可展开的关联同样需逐个声明，关联数据仅返回关联实体的可展开列。
+
+use std::sync::LazyLock;
+
+use sea_orm::{EntityTrait, IdenStatic};
+
+use crate::{
+    dao::query_expand::{Expansion, Expansions, declared},
+    entity::{audit_log, endpoint, menu, report, report_detail, schedule, task, user},
+    error::Error,
+};
+
+pub trait QueryPolicy: EntityTrait {
+    /// 可作为过滤条件的列
+    const FILTERABLE: &'static [Self::Column];
+    /// 可排序的列
+    const SORTABLE: &'static [Self::Column];
+    /// 可出现在响应中的列
+    const RETURNABLE: &'static [Self::Column];
+    /// 作为关联被展开时可返回的列，默认同 RETURNABLE
+    const EXPANDABLE: &'static [Self::Column] = Self::RETURNABLE;
+
+    fn can_filter(column: &Self::Column) -> bool {
+        contains(Self::FILTERABLE, column)
+    }
+
+    fn can_sort(column: &Self::Column) -> bool {
+        contains(Self::SORTABLE, column)
+    }
+
+    fn can_return(column: &Self::Column) -> bool {
+        contains(Self::RETURNABLE, column)
+    }
+
+    /// 可通过 expand 展开的关联
+    fn expansions() -> Result<&'static [Expansion], Error> {
+        Ok(&[])
+    }
+}
+
+fn contains<C: IdenStatic>(columns: &[C], column: &C) -> bool {
+    columns.iter().any(|c| c.as_str() == column.as_str())
+}
+
+impl QueryPolicy for user::Entity {
+    const FILTERABLE: &'static [user::Column] = &[
+        user::Column::Id,
+        user::Column::Username,
+        user::Column::Role,
+        user::Column::EnableFlag,
+        user::Column::MustChangePassword,
+        user::Column::Locale,
+        user::Column::CreatedAt,
+        user::Column::UpdatedAt,
+    ];
+    const SORTABLE: &'static [user::Column] = &[
+        user::Column::Id,
+        user::Column::Username,
+        user::Column::Role,
+        user::Column::EnableFlag,
+        user::Column::CreatedAt,
+        user::Column::UpdatedAt,
+    ];
+    // password、salt 不对外返回
+    const RETURNABLE: &'static [user::Column] = &[
+        user::Column::Id,
+        user::Column::Username,
+        user::Column::Role,
+        user::Column::EnableFlag,
+        user::Column::MustChangePassword,
+        user::Column::Locale,
+        user::Column::CreatedAt,
+        user::Column::UpdatedAt,
+    ];
+}
+
+impl QueryPolicy for menu::Entity {
+    const FILTERABLE: &'static [menu::Column] = &[
+        menu::Column::Id,
+        menu::Column::ParentId,
+        menu::Column::Path,
+        menu::Column::Name,
+        menu::Column::Component,
+        menu::Column::Sort,
+        menu::Column::Status,
+        menu::Column::CreateTime,
+        menu::Column::UpdateTime,
+    ];
+    const SORTABLE: &'static [menu::Column] = &[
+        menu::Column::Id,
+        menu::Column::Name,
+        menu::Column::Sort,
+        menu::Column::CreateTime,
+        menu::Column::UpdateTime,
+    ];
+    const RETURNABLE: &'static [menu::Column] = &[
+        menu::Column::Id,
+        menu::Column::ParentId,
+        menu::Column::Path,
+        menu::Column::Name,
+        menu::Column::Component,
+        menu::Column::Redirect,
+        menu::Column::Sort,
+        menu::Column::Meta,
+        menu::Column::Status,
+        menu::Column::Remark,
+        menu::Column::CreateTime,
+        menu::Column::UpdateTime,
+    ];
+}
+
+impl QueryPolicy for endpoint::Entity {
+    const FILTERABLE: &'static [endpoint::Column] = &[
+        endpoint::Column::Id,
+        endpoint::Column::Name,
+        endpoint::Column::Code,
+        endpoint::Column::Method,
+        endpoint::Column::DomainCode,
+        endpoint::Column::Path,
+        endpoint::Column::IsActive,
+        endpoint::Column::CreatedAt,
+        endpoint::Column::UpdatedAt,
+    ];
+    const SORTABLE: &'static [endpoint::Column] = &[
+        endpoint::Column::Id,
+        endpoint::Column::Name,
+        endpoint::Column::Code,
+        endpoint::Column::CreatedAt,
+        endpoint::Column::UpdatedAt,
+    ];
+    const RETURNABLE: &'static [endpoint::Column] = &[
+        endpoint::Column::Id,
+        endpoint::Column::Name,
+        endpoint::Column::Code,
+        endpoint::Column::Method,
+        endpoint::Column::DomainCode,
+        endpoint::Column::Path,
+        endpoint::Column::Description,
+        endpoint::Column::IsActive,
+        endpoint::Column::CreatedAt,
+        endpoint::Column::UpdatedAt,
+    ];
+}
+
+impl QueryPolicy for audit_log::Entity {
+    const FILTERABLE: &'static [audit_log::Column] = &[
+        audit_log::Column::Id,
+        audit_log::Column::TraceId,
+        audit_log::Column::ActorId,
+        audit_log::Column::ActorName,
+        audit_log::Column::ImpersonatorId,
+        audit_log::Column::ImpersonatorName,
+        audit_log::Column::Ip,
+        audit_log::Column::Method,
+        audit_log::Column::Path,
+        audit_log::Column::Action,
+        audit_log::Column::Entity,
+        audit_log::Column::EntityId,
+        audit_log::Column::CreatedAt,
+    ];
+    const SORTABLE: &'static [audit_log::Column] =
+        &[audit_log::Column::Id, audit_log::Column::CreatedAt];
+    const RETURNABLE: &'static [audit_log::Column] = &[
+        audit_log::Column::Id,
+        audit_log::Column::TraceId,
+        audit_log::Column::ActorId,
+        audit_log::Column::ActorName,
+        audit_log::Column::ImpersonatorId,
+        audit_log::Column::ImpersonatorName,
+        audit_log::Column::Ip,
+        audit_log::Column::Method,
+        audit_log::Column::Path,
+        audit_log::Column::Action,
+        audit_log::Column::Entity,
+        audit_log::Column::EntityId,
+        audit_log::Column::BeforeData,
+        audit_log::Column::AfterData,
+        audit_log::Column::CreatedAt,
+    ];
+}
+
+impl QueryPolicy for task::Entity {
+    const FILTERABLE: &'static [task::Column] = &[
+        task::Column::Id,
+        task::Column::Version,
+        task::Column::FilePath,
+        task::Column::FuncName,
+        task::Column::CronExpression,
+        task::Column::CreatedAt,
+        task::Column::UpdatedAt,
+    ];
+    const SORTABLE: &'static [task::Column] = &[
+        task::Column::Id,
+        task::Column::Version,
+        task::Column::CreatedAt,
+        task::Column::UpdatedAt,
+    ];
+    const RETURNABLE: &'static [task::Column] = &[
+        task::Column::Id,
+        task::Column::Version,
+        task::Column::FilePath,
+        task::Column::FuncName,
+        task::Column::CronExpression,
+        task::Column::Description,
+        task::Column::CreatedAt,
+        task::Column::UpdatedAt,
+    ];
+
+    fn expansions() -> Result<&'static [Expansion], Error> {
+        static EXPANSIONS: Expansions = LazyLock::new(|| {
+            Ok(vec![
+                Expansion::of::<task::Entity, schedule::Entity>("schedules")?,
+                Expansion::of::<task::Entity, report::Entity>("reports")?,
+            ])
+        });
+        declared(&EXPANSIONS)
+    }
+}
+
+impl QueryPolicy for schedule::Entity {
+    const FILTERABLE: &'static [schedule::Column] = &[
+        schedule::Column::Id,
+        schedule::Column::TaskId,
+        schedule::Column::CronExpression,
+        schedule::Column::Enabled,
+        schedule::Column::CreatedAt,
+        schedule::Column::UpdatedAt,
+    ];
+    const SORTABLE: &'static [schedule::Column] = &[
+        schedule::Column::Id,
+        schedule::Column::TaskId,
+        schedule::Column::CreatedAt,
+        schedule::Column::UpdatedAt,
+    ];
+    const RETURNABLE: &'static [schedule::Column] = &[
+        schedule::Column::Id,
+        schedule::Column::TaskId,
+        schedule::Column::CronExpression,
+        schedule::Column::Enabled,
+        schedule::Column::CreatedAt,
+        schedule::Column::UpdatedAt,
+    ];
+
+    fn expansions() -> Result<&'static [Expansion], Error> {
+        static EXPANSIONS: Expansions = LazyLock::new(|| {
+            Ok(vec![Expansion::of::<schedule::Entity, task::Entity>(
+                "task",
+            )?])
+        });
+        declared(&EXPANSIONS)
+    }
+}
+
+impl QueryPolicy for report::Entity {
+    const FILTERABLE: &'static [report::Column] = &[
+        report::Column::Id,
+        report::Column::TaskId,
+        report::Column::Version,
+        report::Column::Status,
+        report::Column::CreatedAt,
+        report::Column::UpdatedAt,
+    ];
+    const SORTABLE: &'static [report::Column] = &[
+        report::Column::Id,
+        report::Column::TaskId,
+        report::Column::Version,
+        report::Column::CreatedAt,
+    ];
+    const RETURNABLE: &'static [report::Column] = &[
+        report::Column::Id,
+        report::Column::TaskId,
+        report::Column::Version,
+        report::Column::Status,
+        report::Column::CreatedAt,
+        report::Column::UpdatedAt,
+    ];
+
+    fn expansions() -> Result<&'static [Expansion], Error> {
+        static EXPANSIONS: Expansions = LazyLock::new(|| {
+            Ok(vec![
+                Expansion::of::<report::Entity, task::Entity>("task")?,
+                Expansion::of::<report::Entity, report_detail::Entity>("details")?,
+            ])
+        });
+        declared(&EXPANSIONS)
+    }
+}
+
+impl QueryPolicy for report_detail::Entity {
+    const FILTERABLE: &'static [report_detail::Column] = &[
+        report_detail::Column::Id,
+        report_detail::Column::ReportId,
+        report_detail::Column::ContentType,
+        report_detail::Column::CreatedAt,
+    ];
+    const SORTABLE: &'static [report_detail::Column] = &[
+        report_detail::Column::Id,
+        report_detail::Column::ReportId,
+        report_detail::Column::CreatedAt,
+    ];
+    const RETURNABLE: &'static [report_detail::Column] = &[
+        report_detail::Column::Id,
+        report_detail::Column::ReportId,
+        report_detail::Column::ContentType,
+        report_detail::Column::Content,
+        report_detail::Column::CreatedAt,
+    ];
+    // content 体积大，仅在直接查询明细时返回
+    const EXPANDABLE: &'static [report_detail::Column] = &[
+        report_detail::Column::Id,
+        report_detail::Column::ReportId,
+        report_detail::Column::ContentType,
+        report_detail::Column::CreatedAt,
+    ];
+
+    fn expansions() -> Result<&'static [Expansion], Error> {
+        static EXPANSIONS: Expansions = LazyLock::new(|| {
+            Ok(vec![
+                Expansion::of::<report_detail::Entity, report::Entity>("report")?,
+            ])
+        });
+        declared(&EXPANSIONS)
+    }
+}
+
+#[cfg(test)]
+mod test_query_policy {
+    use super::*;
+
+    #[test]
+    fn test_user_columns() {
+        assert!(user::Entity::can_return(&user::Column::Username));
+        assert!(!user::Entity::can_return(&user::Column::Password));
+        assert!(!user::Entity::can_filter(&user::Column::Salt));
+        assert!(!user::Entity::can_sort(&user::Column::Password));
+    }
+
+    #[test]
+    fn test_expansions_declared() {
+        // 关联声明有误（如复合键）时在此暴露，而非等到请求时
+        assert_eq!(task::Entity::expansions().unwrap().len(), 2);
+        assert_eq!(schedule::Entity::expansions().unwrap().len(), 1);
+        assert_eq!(report::Entity::expansions().unwrap().len(), 2);
+        assert_eq!(report_detail::Entity::expansions().unwrap().len(), 1);
+        assert!(user::Entity::expansions().unwrap().is_empty());
+    }
+}
@@ -1,5 +1,13 @@
-use crate::{Error, entity, vo::user_vo};
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
+use crate::{
+    Error,
+    dao::{AuditAction, AuditDao},
+    entity,
+    utils::{password, random},
+    vo::user_vo,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityName, EntityTrait, QueryFilter,
+};
 
 pub struct UserDao;
 
@@ -10,40 +18,174 @@ impl UserDao {
     ) -> Result<entity::user::Model, Error> {
         let user = entity::user::ActiveModel {
             username: Set(parm.username),
-            password: Set(parm.password),
-            salt: Set(parm.salt),
+            password: Set(password::hash(&parm.password).await?),
             role: Set(1),
             enable_flag: Set(1),
             ..Default::default()
         };
-        Ok(user.insert(db).await?)
+        let created = user.insert(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Create,
+            entity::user::Entity.table_name(),
+            created.id,
+            None,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
+    }
+
+    /// 创建外部身份用户，密码为随机值，只能通过单点登录
+    pub async fn insert_external(
+        db: &sea_orm::DbConn,
+        username: &str,
+        role: i32,
+    ) -> Result<entity::user::Model, Error> {
+        let user = entity::user::ActiveModel {
+            username: Set(username.to_string()),
+            password: Set(password::hash(&random::token(32)).await?),
+            role: Set(role),
+            enable_flag: Set(1),
+            ..Default::default()
+        };
+        let created = user.insert(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Create,
+            entity::user::Entity.table_name(),
+            created.id,
+            None,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
+    }
+
+    /// 更新角色
+    pub async fn update_role(
+        db: &sea_orm::DbConn,
+        id: i64,
+        role: i32,
+    ) -> Result<entity::user::Model, Error> {
+        let model = entity::user::ActiveModel {
+            id: Set(id),
+            role: Set(role),
+            ..Default::default()
+        };
+        Self::update_audited(db, id, model).await
+    }
+
+    /// 更新语言偏好，None 表示按 Accept-Language 选择
+    pub async fn update_locale(
+        db: &sea_orm::DbConn,
+        id: i64,
+        locale: Option<String>,
+    ) -> Result<entity::user::Model, Error> {
+        let model = entity::user::ActiveModel {
+            id: Set(id),
+            locale: Set(locale),
+            ..Default::default()
+        };
+        Self::update_audited(db, id, model).await
     }
 
     pub async fn update_by_id(
         db: &sea_orm::DbConn,
         parm: &user_vo::UpdateReq,
     ) -> Result<entity::user::Model, Error> {
-        let model = entity::user::Entity::find_by_id(parm.id)
+        let before = entity::user::Entity::find_by_id(parm.id)
             .one(db)
             .await?
             .ok_or_else(|| Error::NotFound(format!("ID {} 不存在", parm.id)))?;
-        let mut model: entity::user::ActiveModel = model.into();
+        let mut model: entity::user::ActiveModel = before.clone().into();
 
         // 遍历字段，只更新有值的字段
         if let Some(username) = &parm.username {
             model.username = Set(username.clone());
         }
-        if let Some(password) = &parm.password {
-            model.password = Set(password.clone());
-        }
-        if let Some(salt) = &parm.salt {
-            model.salt = Set(Some(salt.clone()));
-        }
         if let Some(role) = &parm.role {
-            model.role = Set(role.clone());
+            model.role = Set(*role);
         }
+        if let Some(enable_flag) = &parm.enable_flag {
+            model.enable_flag = Set(*enable_flag);
+        }
+
+        let result = model.update(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Update,
+            entity::user::Entity.table_name(),
+            result.id,
+            Some(&before),
+            Some(&result),
+        )
+        .await;
+        Ok(result)
+    }
+
+    /// 更新密码，传入的必须是已哈希的值
+    pub async fn update_password(
+        db: &sea_orm::DbConn,
+        id: i64,
+        password_hash: &str,
+    ) -> Result<(), Error> {
+        let model = entity::user::ActiveModel {
+            id: Set(id),
+            password: Set(password_hash.to_string()),
+            ..Default::default()
+        };
+        Self::update_audited(db, id, model).await?;
+        Ok(())
+    }
 
+    /// 修改密码并设置“须修改密码”标记，传入的必须是已哈希的值
+    pub async fn change_password(
+        db: &sea_orm::DbConn,
+        id: i64,
+        password_hash: &str,
+        must_change_password: i8,
+    ) -> Result<entity::user::Model, Error> {
+        let model = entity::user::ActiveModel {
+            id: Set(id),
+            password: Set(password_hash.to_string()),
+            must_change_password: Set(must_change_password),
+            ..Default::default()
+        };
+        Self::update_audited(db, id, model).await
+    }
+
+    pub async fn set_must_change_password(
+        db: &sea_orm::DbConn,
+        id: i64,
+        must_change_password: i8,
+    ) -> Result<(), Error> {
+        let model = entity::user::ActiveModel {
+            id: Set(id),
+            must_change_password: Set(must_change_password),
+            ..Default::default()
+        };
+        Self::update_audited(db, id, model).await?;
+        Ok(())
+    }
+
+    /// 按主键更新部分字段，并记录审计日志
+    async fn update_audited(
+        db: &sea_orm::DbConn,
+        id: i64,
+        model: entity::user::ActiveModel,
+    ) -> Result<entity::user::Model, Error> {
+        let before = entity::user::Entity::find_by_id(id).one(db).await?;
         let result = model.update(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Update,
+            entity::user::Entity.table_name(),
+            id,
+            before.as_ref(),
+            Some(&result),
+        )
+        .await;
         Ok(result)
     }
 
@@ -0,0 +1,65 @@
+use crate::{
+    Error,
+    dao::{AuditAction, AuditDao},
+    entity,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityName, EntityTrait, QueryFilter,
+};
+
+pub struct UserIdentityDao;
+
+impl UserIdentityDao {
+    /// 按身份提供方与用户标识查询绑定关系
+    pub async fn query_by_subject(
+        db: &sea_orm::DbConn,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<Option<entity::user_identity::Model>, Error> {
+        Ok(entity::user_identity::Entity::find()
+            .filter(entity::user_identity::Column::Issuer.eq(issuer))
+            .filter(entity::user_identity::Column::Subject.eq(subject))
+            .one(db)
+            .await?)
+    }
+
+    pub async fn insert(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+        issuer: &str,
+        subject: &str,
+        email: Option<String>,
+    ) -> Result<entity::user_identity::Model, Error> {
+        let model = entity::user_identity::ActiveModel {
+            user_id: Set(user_id),
+            issuer: Set(issuer.to_string()),
+            subject: Set(subject.to_string()),
+            email: Set(email),
+            last_login_at: Set(Some(chrono::Local::now().naive_local())),
+            ..Default::default()
+        };
+        let created = model.insert(db).await?;
+        AuditDao::record(
+            db,
+            AuditAction::Create,
+            entity::user_identity::Entity.table_name(),
+            created.id,
+            None,
+            Some(&created),
+        )
+        .await;
+        Ok(created)
+    }
+
+    /// 记录最近登录时间并同步邮箱
+    pub async fn touch(db: &sea_orm::DbConn, id: i64, email: Option<String>) -> Result<(), Error> {
+        let model = entity::user_identity::ActiveModel {
+            id: Set(id),
+            email: Set(email),
+            last_login_at: Set(Some(chrono::Local::now().naive_local())),
+            ..Default::default()
+        };
+        model.update(db).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    Error,
+    dao::{AuditAction, AuditDao},
+    entity,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, EntityName, EntityTrait, sea_query::OnConflict};
+
+pub struct UserTotpDao;
+
+impl UserTotpDao {
+    pub async fn query_by_user(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+    ) -> Result<Option<entity::user_totp::Model>, Error> {
+        Ok(entity::user_totp::Entity::find_by_id(user_id)
+            .one(db)
+            .await?)
+    }
+
+    /// 写入待确认的密钥与恢复码，覆盖此前未完成的绑定
+    pub async fn upsert_pending(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+        secret: &str,
+        recovery_codes: serde_json::Value,
+    ) -> Result<(), Error> {
+        let before = Self::query_by_user(db, user_id).await?;
+        let model = entity::user_totp::ActiveModel {
+            user_id: Set(user_id),
+            secret: Set(secret.to_string()),
+            enabled: Set(0),
+            recovery_codes: Set(recovery_codes),
+            last_used_step: Set(0),
+            ..Default::default()
+        };
+        entity::user_totp::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(entity::user_totp::Column::UserId)
+                    .update_columns([
+                        entity::user_totp::Column::Secret,
+                        entity::user_totp::Column::Enabled,
+                        entity::user_totp::Column::RecoveryCodes,
+                        entity::user_totp::Column::LastUsedStep,
+                    ])
+                    .to_owned(),
+            )
+            .exec(db)
+            .await?;
+        let after = Self::query_by_user(db, user_id).await?;
+        let action = match before {
+            Some(_) => AuditAction::Update,
+            None => AuditAction::Create,
+        };
+        AuditDao::record(
+            db,
+            action,
+            entity::user_totp::Entity.table_name(),
+            user_id,
+            before.as_ref(),
+            after.as_ref(),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// 记录验证结果：启用状态、已使用的时间步与剩余恢复码
+    pub async fn update_state(
+        db: &sea_orm::DbConn,
+        user_id: i64,
+        enabled: i8,
+        last_used_step: i64,
+        recovery_codes: serde_json::Value,
+    ) -> Result<(), Error> {
+        let model = entity::user_totp::ActiveModel {
+            user_id: Set(user_id),
+            enabled: Set(enabled),
+            last_used_step: Set(last_used_step),
+            recovery_codes: Set(recovery_codes),
+            ..Default::default()
+        };
+        model.update(db).await?;
+        Ok(())
+    }
+
+    pub async fn delete_by_user(db: &sea_orm::DbConn, user_id: i64) -> Result<u64, Error> {
+        let before = Self::query_by_user(db, user_id).await?;
+        let result = entity::user_totp::Entity::delete_by_id(user_id)
+            .exec(db)
+            .await?;
+        if result.rows_affected > 0 {
+            AuditDao::record(
+                db,
+                AuditAction::Delete,
+                entity::user_totp::Entity.table_name(),
+                user_id,
+                before.as_ref(),
+                None,
+            )
+            .await;
+        }
+        Ok(result.rows_affected)
+    }
+}
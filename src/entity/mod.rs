@@ -1,14 +1,28 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0.0-rc.11
 
+pub mod api_key;
+pub mod audit_log;
+#[allow(dead_code)]
 pub mod custom_parameter;
+#[allow(dead_code)]
 pub mod database_info;
+#[allow(dead_code)]
 pub mod domain;
 pub mod endpoint;
 pub mod menu;
+pub mod password_history;
+pub mod permission;
 pub mod report;
 pub mod report_detail;
+#[allow(dead_code)]
 pub mod repository;
+#[allow(dead_code)]
 pub mod repository_detail;
+pub mod role;
+pub mod role_menu;
+pub mod role_permission;
 pub mod schedule;
 pub mod task;
 pub mod user;
+pub mod user_identity;
+pub mod user_totp;
@@ -15,6 +15,10 @@ pub struct Model {
     pub status: i32,
     pub created_at: Option<DateTime>,
     pub updated_at: Option<DateTime>,
+    #[sea_orm(belongs_to, from = "task_id", to = "id")]
+    pub task: HasOne<super::task::Entity>,
+    #[sea_orm(has_many)]
+    pub details: HasMany<super::report_detail::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
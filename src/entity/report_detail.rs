@@ -15,6 +15,8 @@ pub struct Model {
     #[sea_orm(column_type = "custom(\"mediumblob\")", nullable)]
     pub content: Option<String>,
     pub created_at: Option<DateTime>,
+    #[sea_orm(belongs_to, from = "report_id", to = "id")]
+    pub report: HasOne<super::report::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -15,6 +15,8 @@ pub struct Model {
     pub enabled: Option<i8>,
     pub created_at: Option<DateTime>,
     pub updated_at: Option<DateTime>,
+    #[sea_orm(belongs_to, from = "task_id", to = "id")]
+    pub task: HasOne<super::task::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
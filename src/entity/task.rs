@@ -18,6 +18,10 @@ pub struct Model {
     pub description: Option<String>,
     pub created_at: Option<DateTime>,
     pub updated_at: Option<DateTime>,
+    #[sea_orm(has_many)]
+    pub schedules: HasMany<super::schedule::Entity>,
+    #[sea_orm(has_many)]
+    pub reports: HasMany<super::report::Entity>,
 }
 
 impl ActiveModelBehavior for ActiveModel {}
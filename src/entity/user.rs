@@ -15,6 +15,10 @@ pub struct Model {
     pub salt: Option<String>,
     pub role: i32,
     pub enable_flag: i8,
+    #[serde(default)]
+    pub must_change_password: i8,
+    #[serde(default)]
+    pub locale: Option<String>,
     pub created_at: Option<DateTime>,
     pub updated_at: Option<DateTime>,
 }
@@ -1,20 +1,47 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use heck::ToLowerCamelCase;
 use serde::{Deserialize, Serialize};
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    collections::BTreeMap,
+};
 use thiserror::Error;
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+use crate::core::{
+    config::{CONFIG, ResponseFormat},
+    context, i18n,
+};
 
 /// 统一的响应格式
 pub type Result<T> = std::result::Result<Resp<T>, Error>;
 
+/// 字段级校验错误：字段路径 -> 错误信息列表。
+/// 路径与请求 JSON 一致使用 camelCase，嵌套结构以 `.` 连接，列表元素为 `[下标]`，如 `items[0].name`
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+/// 错误的底层原因
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 构造错误时捕获的调用栈；用别名避开 thiserror 对 `Backtrace` 字段的 nightly provide 处理
+type StackTrace = Backtrace;
+
 #[derive(Deserialize, Serialize)]
 pub struct Resp<T> {
     code: u32,
     msg: String,
+    /// 请求的 trace_id，用户反馈问题时据此检索日志
+    #[serde(rename = "traceId", skip_serializing_if = "Option::is_none")]
+    trace_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     data: Option<T>,
+    /// 参数校验失败时返回，供前端表单定位字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<FieldErrors>,
 }
 
 impl<T> Resp<T> {
@@ -23,7 +50,9 @@ impl<T> Resp<T> {
         Resp {
             code: 0,
             msg: "success".to_string(),
+            trace_id: current_trace_id(),
             data: Some(data),
+            errors: None,
         }
     }
 }
@@ -33,7 +62,54 @@ impl Resp<()> {
         Resp {
             code,
             msg: msg.into(),
+            trace_id: current_trace_id(),
             data: None,
+            errors: None,
+        }
+    }
+
+    /// 附带字段级校验错误
+    pub(crate) fn with_errors(mut self, errors: FieldErrors) -> Self {
+        self.errors = Some(errors);
+        self
+    }
+}
+
+fn current_trace_id() -> Option<String> {
+    context::request()
+        .map(|r| r.trace_id)
+        .filter(|id| !id.is_empty())
+}
+
+/// 将校验错误展开为字段路径 -> 错误信息
+pub fn field_errors(errors: &ValidationErrors) -> FieldErrors {
+    let mut out = FieldErrors::new();
+    collect_field_errors(errors, "", &mut out);
+    out
+}
+
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, out: &mut FieldErrors) {
+    for (field, kind) in errors.errors() {
+        let field = field.to_lower_camel_case();
+        let path = if prefix.is_empty() {
+            field
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                let messages = errors.iter().map(|e| match &e.message {
+                    Some(message) => i18n::t(message),
+                    None => e.code.to_string(),
+                });
+                out.entry(path).or_default().extend(messages);
+            }
+            ValidationErrorsKind::Struct(errors) => collect_field_errors(errors, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_field_errors(errors, &format!("{}[{}]", path, index), out);
+                }
+            }
         }
     }
 }
@@ -51,6 +127,7 @@ impl<T: Serialize> IntoResponse for Resp<T> {
 }
 
 /// 业务错误枚举
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum Error {
     /// ==================== 以下为通用错误 ====================
@@ -58,16 +135,32 @@ pub enum Error {
     #[error("参数错误: {0}")]
     InvalidParameter(#[from] validator::ValidationErrors),
 
+    /// 请求体不是合法的 JSON，或与目标结构不匹配
+    #[error("请求体格式错误: {0}")]
+    InvalidJson(String),
+
     /// 数据库操作失败
-    #[error("数据库错误: {0}")]
-    DatabaseError(#[from] sea_orm::DbErr),
+    #[error("数据库错误: {source}")]
+    DatabaseError {
+        #[source]
+        source: sea_orm::DbErr,
+        trace: StackTrace,
+    },
 
-    #[error("BB8 池错误: {0}")]
-    RedisPoolError(#[from] bb8::RunError<redis::RedisError>),
+    #[error("BB8 池错误: {source}")]
+    RedisPoolError {
+        #[source]
+        source: bb8::RunError<redis::RedisError>,
+        trace: StackTrace,
+    },
 
     /// redis 操作失败
-    #[error("redis 错误: {0}")]
-    RedisError(#[from] redis::RedisError),
+    #[error("redis 错误: {source}")]
+    RedisError {
+        #[source]
+        source: redis::RedisError,
+        trace: StackTrace,
+    },
 
     /// ==================== 以下为业务相关错误 ====================
     /// 未查询到相关数据
@@ -82,17 +175,36 @@ pub enum Error {
     #[error("授权失败: {0}")]
     Unauthorized(String),
 
+    /// 认证失败：账号密码、验证码或一次性令牌无效
+    #[error("{0}")]
+    InvalidCredentials(String),
+
     /// 外部 API 调用失败
-    #[error("外部服务调用失败: {0}")]
-    ExternalServiceError(String),
+    #[error("外部服务调用失败: {message}")]
+    ExternalServiceError {
+        message: String,
+        #[source]
+        source: Option<BoxError>,
+        trace: StackTrace,
+    },
 
     /// 网络请求失败（超时、断开等）
-    #[error("网络错误: {0}")]
-    NetworkError(String),
+    #[error("网络错误: {message}")]
+    NetworkError {
+        message: String,
+        #[source]
+        source: Option<BoxError>,
+        trace: StackTrace,
+    },
 
     /// IO 错误
-    #[error("IO 错误: {0}")]
-    IOError(String),
+    #[error("IO 错误: {message}")]
+    IOError {
+        message: String,
+        #[source]
+        source: Option<BoxError>,
+        trace: StackTrace,
+    },
 
     /// 资源已存在（比如唯一约束）
     #[error("资源已存在: {0}")]
@@ -102,6 +214,18 @@ pub enum Error {
     #[error("非法状态: {0}")]
     InvalidState(String),
 
+    /// 登录失败次数过多，账号或 IP 被临时锁定（剩余秒数）
+    #[error("登录失败次数过多，请 {0} 秒后重试")]
+    AccountLocked(u64),
+
+    /// 请求过于频繁（剩余秒数）
+    #[error("请求过于频繁，请 {0} 秒后重试")]
+    TooManyRequests(u64),
+
+    /// 须提交图形验证码，或验证码错误
+    #[error("{0}")]
+    CaptchaRequired(String),
+
     /// ==================== 以下为通用查询错误 ====================
     #[error("无效的查询字段: {0}")]
     InvalidQueryField(String),
@@ -110,8 +234,106 @@ pub enum Error {
     BuildQueryError(String),
 
     /// 未知错误
-    #[error("未知错误: {0}")]
-    Unknown(String),
+    #[error("未知错误: {message}")]
+    Unknown {
+        message: String,
+        #[source]
+        source: Option<BoxError>,
+        trace: StackTrace,
+    },
+}
+
+macro_rules! impl_from_source {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(impl From<$ty> for Error {
+            fn from(source: $ty) -> Self {
+                Error::$variant {
+                    source,
+                    trace: Backtrace::capture(),
+                }
+            }
+        })*
+    };
+}
+
+impl_from_source! {
+    sea_orm::DbErr => DatabaseError,
+    bb8::RunError<redis::RedisError> => RedisPoolError,
+    redis::RedisError => RedisError,
+}
+
+/// 需记录日志的错误在构造时捕获调用栈（是否实际采集由 `RUST_BACKTRACE` / `RUST_LIB_BACKTRACE` 控制），
+/// 底层错误通过 `with_source` 挂到 source 链上
+impl Error {
+    pub fn unknown(message: impl Into<String>) -> Self {
+        Error::Unknown {
+            message: message.into(),
+            source: None,
+            trace: Backtrace::capture(),
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Error::IOError {
+            message: message.into(),
+            source: None,
+            trace: Backtrace::capture(),
+        }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Error::NetworkError {
+            message: message.into(),
+            source: None,
+            trace: Backtrace::capture(),
+        }
+    }
+
+    pub fn external(message: impl Into<String>) -> Self {
+        Error::ExternalServiceError {
+            message: message.into(),
+            source: None,
+            trace: Backtrace::capture(),
+        }
+    }
+
+    /// 附加底层错误，仅对携带 source 的变体生效
+    pub fn with_source(mut self, err: impl Into<BoxError>) -> Self {
+        if let Error::Unknown { source, .. }
+        | Error::IOError { source, .. }
+        | Error::NetworkError { source, .. }
+        | Error::ExternalServiceError { source, .. } = &mut self
+        {
+            *source = Some(err.into());
+        }
+        self
+    }
+
+    /// 构造时捕获的调用栈
+    fn backtrace(&self) -> Option<&Backtrace> {
+        match self {
+            Error::DatabaseError { trace, .. }
+            | Error::RedisPoolError { trace, .. }
+            | Error::RedisError { trace, .. }
+            | Error::ExternalServiceError { trace, .. }
+            | Error::NetworkError { trace, .. }
+            | Error::IOError { trace, .. }
+            | Error::Unknown { trace, .. } => Some(trace),
+            _ => None,
+        }
+    }
+
+    /// 错误及其 source 链，逐层以 `: ` 连接
+    fn chain(&self) -> String {
+        let mut chain = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(err) = source {
+            chain.push_str(": ");
+            chain.push_str(&err.to_string());
+            source = err.source();
+        }
+        chain
+    }
 }
 
 impl Error {
@@ -122,17 +344,22 @@ impl Error {
             Error::NotFound(_) => 1002,
             Error::NotLogin => 1003,
             Error::Unauthorized(_) => 1004,
-            Error::DatabaseError(_) => 1005,
-            Error::RedisPoolError(_) => 1006,
-            Error::RedisError(_) => 1006,
-            Error::ExternalServiceError(_) => 1007,
-            Error::NetworkError(_) => 1008,
-            Error::IOError(_) => 1009,
+            Error::DatabaseError { .. } => 1005,
+            Error::RedisPoolError { .. } => 1006,
+            Error::RedisError { .. } => 1006,
+            Error::ExternalServiceError { .. } => 1007,
+            Error::NetworkError { .. } => 1008,
+            Error::IOError { .. } => 1009,
             Error::AlreadyExists(_) => 1010,
             Error::InvalidState(_) => 1011,
             Error::InvalidQueryField(_) => 1012,
             Error::BuildQueryError(_) => 1013,
-            Error::Unknown(_) => 1099,
+            Error::AccountLocked(_) => 1014,
+            Error::CaptchaRequired(_) => 1015,
+            Error::InvalidJson(_) => 1016,
+            Error::InvalidCredentials(_) => 1017,
+            Error::TooManyRequests(_) => 1018,
+            Error::Unknown { .. } => 1099,
         }
     }
 
@@ -140,12 +367,13 @@ impl Error {
     fn should_log(&self) -> bool {
         matches!(
             self,
-            Error::DatabaseError(_)
-                | Error::RedisError(_)
-                | Error::ExternalServiceError(_)
-                | Error::NetworkError(_)
-                | Error::IOError(_)
-                | Error::Unknown(_)
+            Error::DatabaseError { .. }
+                | Error::RedisPoolError { .. }
+                | Error::RedisError { .. }
+                | Error::ExternalServiceError { .. }
+                | Error::NetworkError { .. }
+                | Error::IOError { .. }
+                | Error::Unknown { .. }
         )
     }
 
@@ -154,28 +382,232 @@ impl Error {
         matches!(
             self,
             Error::InvalidParameter(_)
+                | Error::InvalidJson(_)
                 | Error::Unauthorized(_)
+                | Error::InvalidCredentials(_)
                 | Error::NotLogin
                 | Error::AlreadyExists(_)
                 | Error::InvalidState(_)
+                | Error::AccountLocked(_)
+                | Error::TooManyRequests(_)
+                | Error::CaptchaRequired(_)
         )
     }
 }
 
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
+        let trace_id = current_trace_id().unwrap_or_default();
         if self.should_log() {
-            tracing::warn!("业务错误: code={}, err={:?}", self.code(), self);
+            match self.backtrace() {
+                Some(bt) if bt.status() == BacktraceStatus::Captured => tracing::warn!(
+                    "业务错误: trace_id={}, code={}, err={}\n{}",
+                    trace_id,
+                    self.code(),
+                    self.chain(),
+                    bt
+                ),
+                _ => tracing::warn!(
+                    "业务错误: trace_id={}, code={}, err={}",
+                    trace_id,
+                    self.code(),
+                    self.chain()
+                ),
+            }
         } else {
-            tracing::info!("业务错误: code={}, err={:?}", self.code(), self);
+            tracing::info!(
+                "业务错误: trace_id={}, code={}, err={:?}",
+                trace_id,
+                self.code(),
+                self
+            );
         }
 
-        if self.expose() {
-            // 公开错误信息给用户
-            return Resp::error(self.code(), self.to_string()).into_response();
-        } else {
-            // 非公开错误信息，返回通用提示
-            return Resp::error(self.code(), "服务器内部错误").into_response();
+        // 校验错误额外返回字段级明细
+        let errors = match &self {
+            Error::InvalidParameter(errors) => Some(field_errors(errors)),
+            _ => None,
+        };
+        // 公开错误信息给用户，非公开错误返回通用提示；均按请求语言翻译
+        let msg = match &errors {
+            Some(errors) => {
+                let details = errors
+                    .iter()
+                    .flat_map(|(path, messages)| {
+                        messages.iter().map(move |m| format!("{}: {}", path, m))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                i18n::t(&format!("参数错误: {}", details))
+            }
+            None if self.expose() => i18n::t(&self.to_string()),
+            None => i18n::t("服务器内部错误"),
+        };
+
+        if !problem_json_enabled() {
+            let resp = Resp::error(self.code(), msg);
+            return match errors {
+                Some(errors) => resp.with_errors(errors).into_response(),
+                None => resp.into_response(),
+            };
+        }
+
+        let instance = context::request().map(|r| r.trace_id);
+        let body = self.problem(msg, instance, errors);
+        let mut response = (
+            self.status(),
+            [(header::CONTENT_TYPE, PROBLEM_JSON)],
+            Json(body),
+        )
+            .into_response();
+        if let Error::AccountLocked(secs) | Error::TooManyRequests(secs) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+/// problem+json 的媒体类型
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// RFC 7807 错误响应体，在标准字段外附带业务错误码
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    /// 请求的 trace_id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// 业务错误码，与信封格式中的 code 一致
+    pub code: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<FieldErrors>,
+}
+
+/// 是否以 problem+json 格式返回错误：配置开启，或请求的 Accept 头声明了该格式
+fn problem_json_enabled() -> bool {
+    CONFIG.response.format == ResponseFormat::Problem
+        || context::request().is_some_and(|r| r.accept_problem)
+}
+
+impl Error {
+    /// 对应的 HTTP 状态码（仅 problem+json 格式使用，信封格式始终为 200）
+    fn status(&self) -> StatusCode {
+        match self {
+            Error::InvalidParameter(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::InvalidJson(_)
+            | Error::InvalidQueryField(_)
+            | Error::BuildQueryError(_)
+            | Error::CaptchaRequired(_) => StatusCode::BAD_REQUEST,
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::NotLogin | Error::InvalidCredentials(_) => StatusCode::UNAUTHORIZED,
+            Error::Unauthorized(_) => StatusCode::FORBIDDEN,
+            Error::AlreadyExists(_) | Error::InvalidState(_) => StatusCode::CONFLICT,
+            Error::AccountLocked(_) | Error::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::ExternalServiceError { .. } | Error::NetworkError { .. } => {
+                StatusCode::BAD_GATEWAY
+            }
+            Error::RedisPoolError { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Error::DatabaseError { .. }
+            | Error::RedisError { .. }
+            | Error::IOError { .. }
+            | Error::Unknown { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn problem(
+        &self,
+        detail: String,
+        instance: Option<String>,
+        errors: Option<FieldErrors>,
+    ) -> Problem {
+        let status = self.status();
+        Problem {
+            type_uri: String::from("about:blank"),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail,
+            instance,
+            code: self.code(),
+            errors,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_error {
+    use super::*;
+    use validator::ValidationError;
+
+    #[test]
+    fn test_field_errors_paths() {
+        let mut item = ValidationErrors::new();
+        item.add("sort_no", ValidationError::new("range"));
+        let mut nested = ValidationErrors::new();
+        nested.add(
+            "page_size",
+            ValidationError::new("range").with_message("每页条数过大".into()),
+        );
+
+        let mut errors = ValidationErrors::new();
+        errors.add(
+            "user_name",
+            ValidationError::new("length").with_message("用户名过短".into()),
+        );
+        errors.errors_mut().insert(
+            "page".into(),
+            ValidationErrorsKind::Struct(Box::new(nested)),
+        );
+        errors.errors_mut().insert(
+            "items".into(),
+            ValidationErrorsKind::List(BTreeMap::from([(2, Box::new(item))])),
+        );
+
+        let fields = field_errors(&errors);
+        assert_eq!(fields["userName"], vec!["用户名过短"]);
+        assert_eq!(fields["page.pageSize"], vec!["每页条数过大"]);
+        assert_eq!(fields["items[2].sortNo"], vec!["range"]);
+    }
+
+    #[test]
+    fn test_source_chain() {
+        let io = std::io::Error::other("disk full");
+        let err = Error::io("写入文件失败").with_source(io);
+        assert!(err.should_log());
+        assert_eq!(err.chain(), "IO 错误: 写入文件失败: disk full");
+
+        let resp = Resp::<()>::error(err.code(), "服务器内部错误");
+        let value = serde_json::to_value(&resp).unwrap();
+        assert!(value.get("traceId").is_none());
+    }
+
+    #[test]
+    fn test_problem_body() {
+        let problem = Error::NotLogin.problem("未登录或会话过期".into(), Some("abc".into()), None);
+        let value = serde_json::to_value(&problem).unwrap();
+        assert_eq!(value["type"], "about:blank");
+        assert_eq!(value["title"], "Unauthorized");
+        assert_eq!(value["status"], 401);
+        assert_eq!(value["instance"], "abc");
+        assert_eq!(value["code"], 1003);
+        assert!(value.get("errors").is_none());
+
+        assert_eq!(
+            Error::InvalidCredentials("账号或密码错误".into()).status(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            Error::AccountLocked(60).status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            Error::unknown("").status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
 }
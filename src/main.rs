@@ -9,7 +9,7 @@ mod utils;
 mod vo;
 
 use error::{Error, Result};
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use crate::core::{config::CONFIG, state::AppState};
 
@@ -37,5 +37,10 @@ async fn main() {
         &CONFIG.app.version,
         listener.local_addr().unwrap()
     );
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
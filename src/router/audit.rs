@@ -0,0 +1,16 @@
+use axum::extract::State;
+
+use crate::core::extractor::ValidatedJson;
+use crate::core::state::AppState;
+use crate::dao;
+use crate::vo;
+use crate::{Result, entity};
+
+// 动态查询审计日志
+pub async fn query(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::QueryReq>,
+) -> Result<vo::PageResp<serde_json::Value>> {
+    let page_result = dao::query_by_page::<entity::audit_log::Entity>(&state.db, &parm).await?;
+    Ok(page_result.into())
+}
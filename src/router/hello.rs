@@ -7,7 +7,7 @@ use tracing::info;
 use crate::{
     Result,
     core::{extractor::UserInfo, state::AppState},
-    utils::RedisUtil,
+    utils::{RedisLock, RedisUtil},
 };
 
 /// 从请求头获取 trace_id
@@ -24,8 +24,8 @@ pub async fn hello_extract(user_info: UserInfo, State(state): State<AppState>) -
     {
         let key = "sample_key";
         // 操作 redis 锁
-        let lock = redis
-            .acquire_lock(&key, Duration::from_secs(10), true)
+        let lock: RedisLock = redis
+            .acquire_lock(key, Duration::from_secs(10), true)
             .await?;
 
         // 模拟一些工作
@@ -9,12 +9,17 @@ use tracing::info;
 
 use crate::{
     AppState,
-    core::{self, config::CONFIG, middleware::log_middleware},
+    core::{
+        self, config::CONFIG, constant, extractor::RequirePermission, middleware::log_middleware,
+    },
 };
 
+mod audit;
 mod endpoint;
 mod hello;
 mod menu;
+mod report;
+mod task;
 mod user;
 mod web;
 
@@ -34,36 +39,143 @@ pub fn init(state: AppState) -> Router {
         .fallback(web::static_handler)
 }
 
+/// 声明路由所需的权限码
+fn perm(code: &'static str) -> middleware::FromExtractorLayer<RequirePermission, &'static str> {
+    RequirePermission::layer(code)
+}
+
 fn register_routes(state: AppState) -> Router<AppState> {
     // 公开的路由
     let public_routes = axum::Router::new()
         .route("/user/login", post(user::login))
+        .route("/user/captcha", post(user::captcha))
+        .route("/user/token/refresh", post(user::refresh))
+        .route("/user/login/totp/enroll", post(user::login_totp_enroll))
+        .route("/user/login/totp/verify", post(user::login_totp_verify))
+        .route(
+            "/user/password/reset/confirm",
+            post(user::password_reset_confirm),
+        )
+        .route("/user/oidc/login", post(user::oidc_login))
+        .route("/user/oidc/callback", post(user::oidc_callback))
         .route("/hello/one", get(hello::hello_world))
         .route("/hello/two", post(hello::hello_extract));
 
-    // 需要登陆才能访问的路由
+    // 需要登陆才能访问的路由，每个路由通过 route_layer 声明所需权限
     let auth_router = axum::Router::new()
-        // 用户相关路由
+        // 用户相关路由（仅需登录）
         .route("/user/permission", post(user::permission))
         .route("/user/logout", post(user::logout))
+        .route("/user/impersonate/stop", post(user::impersonate_stop))
+        .route("/user/session/list", post(user::session_list))
+        .route("/user/session/revoke", post(user::session_revoke))
+        .route("/user/session/revoke_all", post(user::session_revoke_all))
+        .route("/user/password/change", post(user::password_change))
+        .route("/user/preference", post(user::preference))
+        .route("/user/totp/enroll", post(user::totp_enroll))
+        .route("/user/totp/activate", post(user::totp_activate))
+        .route("/user/totp/disable", post(user::totp_disable))
+        .route("/user/apikey/create", post(user::api_key_create))
+        .route("/user/apikey/list", post(user::api_key_list))
+        .route("/user/apikey/revoke", post(user::api_key_revoke))
         // user 相关路由
-        .route("/user/create", post(user::create))
-        .route("/user/delete", post(user::delete))
-        .route("/user/update", post(user::update))
-        .route("/user/query", post(user::query))
-        .route("/user/info", post(user::info))
+        .route(
+            "/user/create",
+            post(user::create).route_layer(perm(constant::PERM_USER_WRITE)),
+        )
+        .route(
+            "/user/delete",
+            post(user::delete).route_layer(perm(constant::PERM_USER_WRITE)),
+        )
+        .route(
+            "/user/update",
+            post(user::update).route_layer(perm(constant::PERM_USER_WRITE)),
+        )
+        .route(
+            "/user/password/reset",
+            post(user::password_reset).route_layer(perm(constant::PERM_USER_WRITE)),
+        )
+        .route(
+            "/user/impersonate",
+            post(user::impersonate).route_layer(perm(constant::PERM_USER_IMPERSONATE)),
+        )
+        .route(
+            "/user/unlock",
+            post(user::unlock).route_layer(perm(constant::PERM_USER_WRITE)),
+        )
+        .route(
+            "/user/query",
+            post(user::query).route_layer(perm(constant::PERM_USER_READ)),
+        )
+        .route(
+            "/user/info",
+            post(user::info).route_layer(perm(constant::PERM_USER_READ)),
+        )
         // endpoint 相关路由
-        .route("/endpoint/create", post(endpoint::create))
-        .route("/endpoint/delete", post(endpoint::delete))
-        .route("/endpoint/update", post(endpoint::update))
-        .route("/endpoint/query", post(endpoint::query))
-        .route("/endpoint/info", post(endpoint::info))
+        .route(
+            "/endpoint/create",
+            post(endpoint::create).route_layer(perm(constant::PERM_ENDPOINT_WRITE)),
+        )
+        .route(
+            "/endpoint/delete",
+            post(endpoint::delete).route_layer(perm(constant::PERM_ENDPOINT_WRITE)),
+        )
+        .route(
+            "/endpoint/update",
+            post(endpoint::update).route_layer(perm(constant::PERM_ENDPOINT_WRITE)),
+        )
+        .route(
+            "/endpoint/query",
+            post(endpoint::query).route_layer(perm(constant::PERM_ENDPOINT_READ)),
+        )
+        .route(
+            "/endpoint/info",
+            post(endpoint::info).route_layer(perm(constant::PERM_ENDPOINT_READ)),
+        )
         // menu 相关路由
-        .route("/menu/create", post(menu::create))
-        .route("/menu/delete", post(menu::delete))
-        .route("/menu/update", post(menu::update))
-        .route("/menu/query", post(menu::query))
-        .route("/menu/info", post(menu::info))
+        .route(
+            "/menu/create",
+            post(menu::create).route_layer(perm(constant::PERM_MENU_WRITE)),
+        )
+        .route(
+            "/menu/delete",
+            post(menu::delete).route_layer(perm(constant::PERM_MENU_WRITE)),
+        )
+        .route(
+            "/menu/update",
+            post(menu::update).route_layer(perm(constant::PERM_MENU_WRITE)),
+        )
+        .route(
+            "/menu/query",
+            post(menu::query).route_layer(perm(constant::PERM_MENU_READ)),
+        )
+        .route(
+            "/menu/info",
+            post(menu::info).route_layer(perm(constant::PERM_MENU_READ)),
+        )
+        // 审计日志
+        .route(
+            "/audit/query",
+            post(audit::query).route_layer(perm(constant::PERM_AUDIT_READ)),
+        )
+        // 任务与调度
+        .route(
+            "/task/query",
+            post(task::query).route_layer(perm(constant::PERM_TASK_READ)),
+        )
+        .route(
+            "/schedule/query",
+            post(task::schedule_query).route_layer(perm(constant::PERM_TASK_READ)),
+        )
+        // 测试报告
+        .route(
+            "/report/query",
+            post(report::query).route_layer(perm(constant::PERM_REPORT_READ)),
+        )
+        .route(
+            "/report/detail/query",
+            post(report::detail_query).route_layer(perm(constant::PERM_REPORT_READ)),
+        )
         .layer(middleware::from_fn_with_state(
             state.clone(),
             core::middleware::user_middleware,
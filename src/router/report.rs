@@ -0,0 +1,25 @@
+use axum::extract::State;
+
+use crate::core::extractor::ValidatedJson;
+use crate::core::state::AppState;
+use crate::dao;
+use crate::vo;
+use crate::{Result, entity};
+
+// 动态查询测试报告，可展开 task、details
+pub async fn query(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::QueryReq>,
+) -> Result<vo::PageResp<serde_json::Value>> {
+    let page_result = dao::query_by_page::<entity::report::Entity>(&state.db, &parm).await?;
+    Ok(page_result.into())
+}
+
+// 动态查询报告明细，content 较大，列表场景建议通过 fields 排除；可展开 report
+pub async fn detail_query(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::QueryReq>,
+) -> Result<vo::PageResp<serde_json::Value>> {
+    let page_result = dao::query_by_page::<entity::report_detail::Entity>(&state.db, &parm).await?;
+    Ok(page_result.into())
+}
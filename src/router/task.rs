@@ -0,0 +1,25 @@
+use axum::extract::State;
+
+use crate::core::extractor::ValidatedJson;
+use crate::core::state::AppState;
+use crate::dao;
+use crate::vo;
+use crate::{Result, entity};
+
+// 动态查询任务，可展开 schedules、reports
+pub async fn query(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::QueryReq>,
+) -> Result<vo::PageResp<serde_json::Value>> {
+    let page_result = dao::query_by_page::<entity::task::Entity>(&state.db, &parm).await?;
+    Ok(page_result.into())
+}
+
+// 动态查询调度，可展开 task
+pub async fn schedule_query(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::QueryReq>,
+) -> Result<vo::PageResp<serde_json::Value>> {
+    let page_result = dao::query_by_page::<entity::schedule::Entity>(&state.db, &parm).await?;
+    Ok(page_result.into())
+}
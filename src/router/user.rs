@@ -1,130 +1,640 @@
 use crate::{
-    AppState, Result,
-    core::extractor::UserInfo,
-    dao::{self, UserDao},
+    AppState, Error, Result,
+    core::{
+        config::{AuthMode, CONFIG},
+        cookie::{self, SessionResponse},
+        extractor::{ClientInfo, UserInfo, ValidatedJson},
+        i18n::Locale,
+        jwt, session,
+    },
+    dao::{self, ApiKeyDao, UserDao, UserTotpDao},
     entity,
+    error::Resp,
+    service::{
+        api_key_service, captcha_service, impersonation_service, login_service, menu_service,
+        oidc_service, password_service, totp_service,
+    },
+    utils::password,
     vo::{self, user_vo},
 };
-use axum::{Json, extract::State};
+use axum::{
+    extract::State,
+    http::{HeaderMap, Method},
+};
+use axum_extra::extract::CookieJar;
 use tracing::info;
-use validator::Validate;
+
+/// 签发会话的接口，cookie 模式下 token 写入 cookie
+type SessionResult = std::result::Result<SessionResponse, Error>;
 
 // 用户登录，成功返回 token
 pub async fn login(
     State(state): State<AppState>,
-    Json(payload): Json<user_vo::LoginReq>,
-) -> Result<user_vo::LoginResp> {
-    payload.validate()?;
+    client: ClientInfo,
+    ValidatedJson(payload): ValidatedJson<user_vo::LoginReq>,
+) -> SessionResult {
     info!("User login attempt: {:?}", payload.username);
+    let ip = client.ip.as_deref();
+    login_service::check_locked(&state, &payload.username, ip).await?;
+    captcha_service::check_login(
+        &state,
+        &payload.username,
+        ip,
+        payload.captcha_id.as_deref(),
+        payload.captcha_code.as_deref(),
+    )
+    .await?;
 
     let user = UserDao::query_by_username(&state.db, &payload.username).await?;
 
-    if let Some(user) = user
-        && user.password == payload.password
+    if let Some(mut user) = user
+        && password::verify(&payload.password, &user.password).await?
     {
-        let user = UserInfo::login(user, &state).await?;
-        Ok(user_vo::LoginResp {
-            user_id: user.user_db.id,
-            token: user.token,
-            username: user.user_db.username,
-            role: user.user_db.role,
+        if user.enable_flag != 1 {
+            info!("Disabled user login refused: {}", user.username);
+            return Err(Error::Unauthorized("账号已禁用".into()));
         }
-        .into())
+        // 历史明文密码，登录成功后升级为哈希
+        if !password::is_hashed(&user.password) {
+            let hashed = password::hash(&payload.password).await?;
+            UserDao::update_password(&state.db, user.id, &hashed).await?;
+            info!("Upgraded plaintext password for user: {}", user.username);
+            user.password = hashed;
+        }
+        finish_login(&state, &client, user).await
     } else {
         info!(
             "Invalid username or password for user: {}",
             payload.username
         );
-        Err(crate::Error::Unauthorized("账号或密码错误".into()))
+        if let Some(secs) = login_service::record_failure(&state, &payload.username, ip).await? {
+            return Err(Error::AccountLocked(secs));
+        }
+        Err(Error::InvalidCredentials("账号或密码错误".into()))
+    }
+}
+
+// 获取登录图形验证码
+pub async fn captcha(
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<user_vo::CaptchaResp> {
+    let resp = captcha_service::generate(&state, client.ip.as_deref()).await?;
+    Ok(resp.into())
+}
+
+// 使用 refresh token 换取新的 token 对（仅 JWT 模式），旧的一对立即作废
+pub async fn refresh(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    headers: HeaderMap,
+    method: Method,
+    ValidatedJson(parm): ValidatedJson<user_vo::RefreshReq>,
+) -> SessionResult {
+    if CONFIG.auth.mode != AuthMode::Jwt {
+        return Err(Error::InvalidState("仅 JWT 模式支持刷新 token".into()));
+    }
+    // cookie 模式下请求体可不携带 refresh token，改从 cookie 读取并校验 CSRF
+    let refresh_token = match parm.refresh_token {
+        Some(token) => token,
+        None => {
+            let token = cookie::refresh_token(&headers).ok_or(Error::NotLogin)?;
+            cookie::verify_csrf(&method, &headers)?;
+            token
+        }
+    };
+    let claims = jwt::verify(&refresh_token, jwt::TokenType::Refresh, &state).await?;
+    jwt::consume(&claims, &state).await?;
+    jwt::revoke(&claims.sid, &state).await?;
+
+    // 重新读取用户，角色与权限以数据库为准
+    let user = dao::query_by_id::<entity::user::Entity>(&state.db, claims.sub)
+        .await?
+        .filter(|u| u.enable_flag == 1)
+        .ok_or(Error::NotLogin)?;
+    let user = match claims.act {
+        // 模拟登录刷新后保持模拟状态
+        Some(act) => UserInfo::impersonate(user, act, &client, &state).await?,
+        None => UserInfo::login(user, &client, &state).await?,
+    };
+    Ok(login_resp(user).into())
+}
+
+// 发起单点登录，返回身份提供方的授权地址，并以 cookie 将授权请求绑定到当前浏览器
+pub async fn oidc_login(
+    State(state): State<AppState>,
+) -> std::result::Result<(CookieJar, Resp<user_vo::OidcLoginResp>), Error> {
+    let (resp, binding) = oidc_service::authorize(&state).await?;
+    Ok((cookie::set_oidc_binding(binding), resp.into()))
+}
+
+// 单点登录回调：校验授权码，绑定或创建用户后签发会话
+pub async fn oidc_callback(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    headers: HeaderMap,
+    ValidatedJson(parm): ValidatedJson<user_vo::OidcCallbackReq>,
+) -> std::result::Result<(CookieJar, SessionResponse), Error> {
+    let binding = cookie::oidc_binding(&headers);
+    let identity =
+        oidc_service::exchange(&state, &parm.code, &parm.state, binding.as_deref()).await?;
+    info!(
+        "OIDC login attempt: {} (sub={})",
+        identity.username, identity.subject
+    );
+    let user = oidc_service::resolve_user(&state, identity).await?;
+    let resp = finish_login(&state, &client, user).await?;
+    Ok((cookie::clear_oidc_binding(), resp))
+}
+
+/// 一次认证通过后完成登录：已启用两步验证（或管理员被要求启用）时先返回待验证令牌，否则签发 token。
+/// 失败计数在两步验证通过后才清除，密码与验证码的失败次数累计计算
+async fn finish_login(
+    state: &AppState,
+    client: &ClientInfo,
+    user: entity::user::Model,
+) -> SessionResult {
+    if let Some(two_factor) = totp_service::challenge(state, &user).await? {
+        info!("Two-factor required for user: {}", user.username);
+        return Ok(user_vo::LoginResp {
+            user_id: user.id,
+            token: None,
+            refresh_token: None,
+            username: user.username,
+            role: user.role,
+            must_change_password: user.must_change_password == 1,
+            two_factor: Some(two_factor),
+            impersonator: None,
+        }
+        .into());
     }
+    // IP 维度的计数保留，防止借合法账号重置计数
+    login_service::unlock_user(state, &user.username).await?;
+    let user = UserInfo::login(user, client, state).await?;
+    Ok(login_resp(user).into())
 }
 
-// 获取用户权限信息
-pub async fn permission(user: UserInfo) -> Result<user_vo::PermissionResp> {
+fn login_resp(user: UserInfo) -> user_vo::LoginResp {
+    user_vo::LoginResp {
+        user_id: user.user_db.id,
+        token: Some(user.token),
+        refresh_token: user.refresh_token,
+        username: user.user_db.username,
+        role: user.user_db.role,
+        must_change_password: user.user_db.must_change_password == 1,
+        two_factor: None,
+        impersonator: user.impersonator.map(|admin| admin.username),
+    }
+}
+
+/// 读取待两步验证的登录及其用户
+async fn pending_user(
+    state: &AppState,
+    pending_token: &str,
+) -> std::result::Result<(totp_service::PendingLogin, entity::user::Model), Error> {
+    let pending = totp_service::pending(state, pending_token).await?;
+    let user = dao::query_by_id::<entity::user::Entity>(&state.db, pending.user_id)
+        .await?
+        .filter(|u| u.enable_flag == 1)
+        .ok_or_else(|| Error::InvalidCredentials("登录已失效，请重新登录".into()))?;
+    Ok((pending, user))
+}
+
+// 登录时强制绑定验证器：凭待验证令牌生成密钥与恢复码
+pub async fn login_totp_enroll(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<user_vo::TotpPendingReq>,
+) -> Result<user_vo::TotpEnrollResp> {
+    let (pending, user) = pending_user(&state, &parm.pending_token).await?;
+    if !pending.enroll {
+        return Err(Error::InvalidState("已启用两步验证".into()));
+    }
+    info!("TOTP enrollment during login: {}", user.username);
+    let resp = totp_service::begin_enroll(&state.db, &user).await?;
+    Ok(resp.into())
+}
+
+// 登录第二步：校验验证码（或恢复码），通过后签发 token
+pub async fn login_totp_verify(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::TotpVerifyReq>,
+) -> SessionResult {
+    let (pending, user) = pending_user(&state, &parm.pending_token).await?;
+    let ip = client.ip.as_deref();
+    login_service::check_locked(&state, &user.username, ip).await?;
+
+    let passed = if pending.enroll {
+        totp_service::activate(&state.db, &user, &parm.code).await?
+    } else {
+        totp_service::verify(&state.db, &user, &parm.code).await?
+    };
+    // 验证码同样计入登录失败次数，防止暴力枚举
+    if !passed {
+        info!("Invalid two-factor code for user: {}", user.username);
+        if let Some(secs) = login_service::record_failure(&state, &user.username, ip).await? {
+            return Err(Error::AccountLocked(secs));
+        }
+        return Err(Error::InvalidCredentials("验证码错误".into()));
+    }
+    if !totp_service::complete(&state, &parm.pending_token).await? {
+        return Err(Error::InvalidCredentials("登录已失效，请重新登录".into()));
+    }
+    login_service::unlock_user(&state, &user.username).await?;
+    let user = UserInfo::login(user, &client, &state).await?;
+    Ok(login_resp(user).into())
+}
+
+// 获取用户权限信息：角色、权限码以及角色可见的菜单树
+pub async fn permission(
+    State(state): State<AppState>,
+    user: UserInfo,
+) -> Result<user_vo::PermissionResp> {
+    let roles = dao::query_by_id::<entity::role::Entity>(&state.db, user.user_db.role)
+        .await?
+        .map(|role| vec![role.code])
+        .unwrap_or_default();
+    let menus = menu_service::get_user_menu_tree(&state.db, &user).await?;
     Ok(user_vo::PermissionResp {
-        roles: vec![String::from("admin")],
+        roles,
+        permissions: user.permissions,
+        menus,
         name: user.user_db.username,
         avatar: String::from(""),
         introduction: String::from(""),
+        impersonator: user.impersonator.map(|admin| admin.username),
     }
     .into())
 }
 
 // 用户登出，删除 redis 中的 session
-pub async fn logout(State(state): State<AppState>, user: UserInfo) -> Result<String> {
+pub async fn logout(
+    State(state): State<AppState>,
+    user: UserInfo,
+) -> std::result::Result<(CookieJar, Resp<String>), Error> {
     info!("User logout attempt: {:?}", user.user_db.username);
     user.logout(&state).await?;
-    Ok(format!("Logout successful").into())
+    Ok((cookie::clear(), String::from("Logout successful").into()))
+}
+
+/// 会话与 API Key 的管理需使用交互式登录，API Key 与模拟登录不能管理凭证
+fn deny_delegated(user: &UserInfo) -> std::result::Result<(), Error> {
+    if user.is_api_key() {
+        return Err(Error::Unauthorized("API Key 不能管理登录凭证".into()));
+    }
+    if user.is_impersonated() {
+        return Err(Error::Unauthorized("模拟登录不能管理登录凭证".into()));
+    }
+    Ok(())
+}
+
+// 管理员以指定用户身份登录，返回模拟会话的 token，管理员原会话保持有效
+pub async fn impersonate(
+    user: UserInfo,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    ValidatedJson(parm): ValidatedJson<vo::IdReq>,
+) -> SessionResult {
+    info!(
+        "Impersonate attempt: {:?} -> {}",
+        user.user_db.username, parm.id
+    );
+    let user = impersonation_service::start(&state, &user, parm.id, &client).await?;
+    Ok(login_resp(user).into())
+}
+
+// 结束模拟登录，吊销模拟会话并返回管理员的 token
+pub async fn impersonate_stop(
+    user: UserInfo,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> SessionResult {
+    let user = impersonation_service::stop(&state, &user, &client).await?;
+    Ok(login_resp(user).into())
+}
+
+// 列出当前用户的全部有效会话
+pub async fn session_list(
+    State(state): State<AppState>,
+    user: UserInfo,
+) -> Result<Vec<user_vo::SessionResp>> {
+    deny_delegated(&user)?;
+    let sessions = session::list(&state, user.user_db.id).await?;
+    let sessions = sessions
+        .into_iter()
+        .map(|s| user_vo::SessionResp {
+            current: s.session.id == user.session.id,
+            session_id: s.session.id,
+            issued_at: s.session.issued_at,
+            last_seen: s.session.last_seen,
+            ip: s.session.ip,
+            user_agent: s.session.user_agent,
+            impersonator: s.impersonator.map(|admin| admin.username),
+        })
+        .collect::<Vec<_>>();
+    Ok(sessions.into())
+}
+
+// 吊销当前用户的指定会话
+pub async fn session_revoke(
+    State(state): State<AppState>,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::SessionRevokeReq>,
+) -> Result<bool> {
+    deny_delegated(&user)?;
+    info!(
+        "Revoke session {} of user: {:?}",
+        parm.session_id, user.user_db.username
+    );
+    let revoked = session::revoke(&state, user.user_db.id, &parm.session_id).await?;
+    Ok(revoked.into())
+}
+
+// 吊销当前用户除当前会话外的全部会话，返回吊销数量
+pub async fn session_revoke_all(State(state): State<AppState>, user: UserInfo) -> Result<u64> {
+    deny_delegated(&user)?;
+    info!("Revoke all sessions of user: {:?}", user.user_db.username);
+    let count = session::revoke_all(&state, user.user_db.id, Some(&user.session.id)).await?;
+    Ok(count.into())
+}
+
+fn api_key_resp(model: entity::api_key::Model) -> user_vo::ApiKeyResp {
+    user_vo::ApiKeyResp {
+        scopes: api_key_service::scopes_of(&model),
+        id: model.id,
+        name: model.name,
+        prefix: model.prefix,
+        expires_at: model.expires_at,
+        last_used_at: model.last_used_at,
+        created_at: model.created_at,
+    }
+}
+
+// 为当前用户创建 API Key，完整 key 仅返回这一次
+pub async fn api_key_create(
+    State(state): State<AppState>,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::ApiKeyCreateReq>,
+) -> Result<user_vo::ApiKeyCreateResp> {
+    deny_delegated(&user)?;
+    info!(
+        "Create api key {:?} with scopes {:?} by: {:?}",
+        parm.name, parm.scopes, user.user_db.username
+    );
+    let (model, key) = api_key_service::create(&state, &user, &parm).await?;
+    Ok(user_vo::ApiKeyCreateResp {
+        key,
+        info: api_key_resp(model),
+    }
+    .into())
+}
+
+// 列出当前用户的全部 API Key
+pub async fn api_key_list(
+    State(state): State<AppState>,
+    user: UserInfo,
+) -> Result<Vec<user_vo::ApiKeyResp>> {
+    deny_delegated(&user)?;
+    let keys = ApiKeyDao::query_by_user(&state.db, user.user_db.id).await?;
+    Ok(keys
+        .into_iter()
+        .map(api_key_resp)
+        .collect::<Vec<_>>()
+        .into())
+}
+
+// 吊销当前用户的指定 API Key
+pub async fn api_key_revoke(
+    State(state): State<AppState>,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<vo::IdReq>,
+) -> Result<bool> {
+    deny_delegated(&user)?;
+    info!(
+        "Revoke api key {} of user: {:?}",
+        parm.id, user.user_db.username
+    );
+    let rows = ApiKeyDao::delete_by_user(&state.db, user.user_db.id, parm.id).await?;
+    Ok((rows > 0).into())
+}
+
+// 为当前用户生成两步验证密钥与恢复码，提交验证码确认后生效
+pub async fn totp_enroll(
+    State(state): State<AppState>,
+    user: UserInfo,
+) -> Result<user_vo::TotpEnrollResp> {
+    deny_delegated(&user)?;
+    info!("TOTP enrollment by: {:?}", user.user_db.username);
+    let resp = totp_service::begin_enroll(&state.db, &user.user_db).await?;
+    Ok(resp.into())
+}
+
+// 提交首个验证码，启用两步验证
+pub async fn totp_activate(
+    State(state): State<AppState>,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::TotpCodeReq>,
+) -> Result<bool> {
+    deny_delegated(&user)?;
+    if !totp_service::activate(&state.db, &user.user_db, &parm.code).await? {
+        return Err(Error::Unauthorized("验证码错误".into()));
+    }
+    info!("TOTP enabled for user: {:?}", user.user_db.username);
+    Ok(true.into())
+}
+
+// 停用两步验证，需提交验证码或恢复码
+pub async fn totp_disable(
+    State(state): State<AppState>,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::TotpCodeReq>,
+) -> Result<bool> {
+    deny_delegated(&user)?;
+    if CONFIG.auth.totp.require_for_admin && user.is_admin() {
+        return Err(Error::InvalidState("管理员必须启用两步验证".into()));
+    }
+    if !totp_service::verify(&state.db, &user.user_db, &parm.code).await? {
+        return Err(Error::Unauthorized("验证码错误".into()));
+    }
+    UserTotpDao::delete_by_user(&state.db, user.user_db.id).await?;
+    info!("TOTP disabled for user: {:?}", user.user_db.username);
+    Ok(true.into())
+}
+
+// 修改自己的密码，需验证原密码；成功后吊销其他会话
+pub async fn password_change(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::PasswordChangeReq>,
+) -> SessionResult {
+    deny_delegated(&user)?;
+    let username = user.user_db.username.clone();
+    let ip = client.ip.as_deref();
+    login_service::check_locked(&state, &username, ip).await?;
+
+    // 会话中的用户信息可能已过时，以数据库为准
+    let user_db = dao::query_by_id::<entity::user::Entity>(&state.db, user.user_db.id)
+        .await?
+        .ok_or(Error::NotLogin)?;
+    if !password::verify(&parm.old_password, &user_db.password).await? {
+        info!("Invalid old password for user: {}", username);
+        if let Some(secs) = login_service::record_failure(&state, &username, ip).await? {
+            return Err(Error::AccountLocked(secs));
+        }
+        return Err(Error::Unauthorized("原密码错误".into()));
+    }
+    password_service::check_new_password(&state.db, &user_db, &parm.new_password, "newPassword")
+        .await?;
+    let user_db = password_service::set_password(&state, user_db.id, &parm.new_password).await?;
+    info!("Password changed by user: {}", username);
+    let user = session::rotate(&state, user, user_db, &client).await?;
+    Ok(login_resp(user).into())
+}
+
+// 设置当前用户的语言偏好，传空值恢复为按 Accept-Language 选择。
+// JWT 模式下偏好随 token 签发，刷新 token 后生效
+pub async fn preference(
+    State(state): State<AppState>,
+    user: UserInfo,
+    ValidatedJson(parm): ValidatedJson<user_vo::PreferenceReq>,
+) -> Result<bool> {
+    let locale = parm
+        .locale
+        .as_deref()
+        .and_then(Locale::parse)
+        .map(|l| l.as_str().to_string());
+    let user_db = UserDao::update_locale(&state.db, user.user_db.id, locale).await?;
+    if CONFIG.auth.mode == AuthMode::Session {
+        session::sync_user(&state, &user_db).await?;
+    }
+    Ok(true.into())
+}
+
+// 管理员重置用户密码：返回一次性重置令牌，用户须在下次登录后修改密码。
+// 令牌可直接接管目标账号，仅限管理员本人交互式登录操作
+pub async fn password_reset(
+    user: UserInfo,
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::IdReq>,
+) -> Result<user_vo::PasswordResetResp> {
+    deny_delegated(&user)?;
+    if !user.is_admin() {
+        return Err(Error::Unauthorized("仅管理员可重置密码".into()));
+    }
+    dao::query_by_id::<entity::user::Entity>(&state.db, parm.id)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("ID {} 不存在", parm.id)))?;
+    info!(
+        "Password reset of user {} by: {:?}",
+        parm.id, user.user_db.username
+    );
+    let reset_token = password_service::issue_reset(&state, parm.id).await?;
+    Ok(user_vo::PasswordResetResp {
+        reset_token,
+        expires_in: CONFIG.auth.password.reset_ttl,
+    }
+    .into())
+}
+
+// 凭一次性重置令牌设置新密码，成功后需重新登录
+pub async fn password_reset_confirm(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<user_vo::PasswordResetConfirmReq>,
+) -> Result<bool> {
+    let user_id = password_service::peek_reset(&state, &parm.reset_token).await?;
+    let user_db = dao::query_by_id::<entity::user::Entity>(&state.db, user_id)
+        .await?
+        .ok_or_else(|| Error::InvalidCredentials("重置令牌无效或已过期".into()))?;
+    // 先校验新密码再消费令牌，密码不合规时令牌仍可使用
+    password_service::check_new_password(&state.db, &user_db, &parm.new_password, "newPassword")
+        .await?;
+    if !password_service::consume_reset(&state, &parm.reset_token).await? {
+        return Err(Error::InvalidCredentials("重置令牌无效或已过期".into()));
+    }
+    password_service::set_password(&state, user_id, &parm.new_password).await?;
+    session::revoke_user(&state, user_id).await?;
+    login_service::unlock_user(&state, &user_db.username).await?;
+    info!("Password reset confirmed for user: {}", user_db.username);
+    Ok(true.into())
+}
+
+// 管理员解除账号（及可选 IP）的登录锁定
+pub async fn unlock(
+    user: UserInfo,
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<user_vo::UnlockReq>,
+) -> Result<bool> {
+    info!(
+        "Unlock login of {} (ip: {:?}) by: {:?}",
+        parm.username, parm.ip, user.user_db.username
+    );
+    login_service::unlock_user(&state, &parm.username).await?;
+    if let Some(ip) = &parm.ip {
+        login_service::unlock_ip(&state, ip).await?;
+    }
+    Ok(true.into())
 }
 
 // 创建用户，返回新用户 ID
 pub async fn create(
     user: UserInfo,
     State(state): State<AppState>,
-    Json(parm): Json<user_vo::InsertReq>,
+    ValidatedJson(parm): ValidatedJson<user_vo::InsertReq>,
 ) -> Result<i64> {
-    parm.validate()?;
-    if !user.is_admin() {
-        return Err(crate::Error::Unauthorized("无权限操作".into()));
-    }
+    password_service::check_policy(&parm.password, "password")?;
     info!("Create user attempt by: {:?}", user.user_db.username);
     let u = UserDao::query_by_username(&state.db, &parm.username).await?;
     if u.is_some() {
-        return Err(crate::Error::AlreadyExists("用户名已存在".into()));
+        return Err(Error::AlreadyExists("用户名已存在".into()));
     }
     let user = UserDao::insert(&state.db, parm).await?;
+    password_service::record_history(&state.db, user.id, &user.password).await?;
     Ok(user.id.into())
 }
 
 // 删除用户
-pub async fn delete(State(state): State<AppState>, Json(parm): Json<vo::IdReq>) -> Result<u64> {
+pub async fn delete(
+    State(state): State<AppState>,
+    ValidatedJson(parm): ValidatedJson<vo::IdReq>,
+) -> Result<u64> {
     let lins = dao::delete_by_id::<entity::user::Entity>(&state.db, parm.id).await?;
+    // 已删除用户的在线会话立即失效
+    session::revoke_user(&state, parm.id).await?;
     Ok(lins.into())
 }
 
 // 动态修改用户信息
 pub async fn update(
     State(state): State<AppState>,
-    Json(parm): Json<user_vo::UpdateReq>,
+    ValidatedJson(parm): ValidatedJson<user_vo::UpdateReq>,
 ) -> Result<i64> {
-    parm.validate()?;
-    let users_id = UserDao::update_by_id(&state.db, &parm).await?;
-    Ok(users_id.id.into())
+    let user = UserDao::update_by_id(&state.db, &parm).await?;
+    // 禁用、角色变更等需立即作用到该用户的在线会话
+    session::sync_user(&state, &user).await?;
+    Ok(user.id.into())
 }
 
 // 动态查询，返回用户列表
 pub async fn query(
     State(state): State<AppState>,
-    Json(parm): Json<vo::QueryReq>,
-) -> Result<vo::PageResp<user_vo::SearchResp>> {
-    parm.validate()?;
+    ValidatedJson(parm): ValidatedJson<vo::QueryReq>,
+) -> Result<vo::PageResp<serde_json::Value>> {
     info!("Get all users with params: {:?}", parm);
     let page_result = dao::query_by_page::<entity::user::Entity>(&state.db, &parm).await?;
-    let page_result = page_result.map(|user| user_vo::SearchResp {
-        id: user.id,
-        username: user.username,
-        password: user.password,
-        salt: user.salt,
-        role: user.role,
-        enable_flag: user.enable_flag,
-        created_at: user.created_at,
-        updated_at: user.updated_at,
-    });
     Ok(page_result.into())
 }
 
 // 查询详情信息
 pub async fn info(
     State(state): State<AppState>,
-    Json(parm): Json<vo::IdReq>,
+    ValidatedJson(parm): ValidatedJson<vo::IdReq>,
 ) -> Result<Option<user_vo::SearchResp>> {
     let user = dao::query_by_id::<entity::user::Entity>(&state.db, parm.id)
         .await?
         .map(|user| user_vo::SearchResp {
             id: user.id,
             username: user.username,
-            password: user.password,
-            salt: user.salt,
             role: user.role,
             enable_flag: user.enable_flag,
             created_at: user.created_at,
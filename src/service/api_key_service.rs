@@ -0,0 +1,106 @@
+//! 机器客户端 API Key
+//!
+//! - key 仅在创建时返回一次，数据库只保存 SHA-256 摘要与用于识别的前缀
+//! - 每个 key 声明授权范围（权限码），实际权限为所属用户权限与授权范围的交集
+//! - 每次请求都会重新读取所属用户，用户被禁用或删除后 key 立即失效
+
+use sea_orm::ActiveValue::Set;
+
+use crate::{
+    AppState, Error,
+    core::{constant, extractor::UserInfo, session::SessionMeta},
+    dao::{self, ApiKeyDao, PermissionDao},
+    entity,
+    utils::{digest, random},
+    vo::user_vo,
+};
+
+/// 生成新的 key，返回 (明文, 前缀, 摘要)
+fn generate() -> (String, String, String) {
+    let key = format!(
+        "{}{}",
+        constant::API_KEY_PREFIX,
+        random::token(constant::API_KEY_BYTES)
+    );
+    let prefix = key[..constant::API_KEY_PREFIX.len() + 8].to_string();
+    let key_hash = digest::sha256_hex(&key);
+    (key, prefix, key_hash)
+}
+
+/// 解析 key 的授权范围
+pub fn scopes_of(model: &entity::api_key::Model) -> Vec<String> {
+    serde_json::from_value(model.scopes.clone()).unwrap_or_default()
+}
+
+/// 为当前用户创建 API Key，授权范围不能超出用户自身权限，返回 key 记录与明文
+pub async fn create(
+    state: &AppState,
+    user: &UserInfo,
+    parm: &user_vo::ApiKeyCreateReq,
+) -> Result<(entity::api_key::Model, String), Error> {
+    if let Some(code) = parm.scopes.iter().find(|c| !user.has_permission(c)) {
+        return Err(Error::Unauthorized(format!("无权授予权限: {}", code)));
+    }
+    let mut scopes = parm.scopes.clone();
+    scopes.sort();
+    scopes.dedup();
+
+    let (key, prefix, key_hash) = generate();
+    let expires_at = parm
+        .expire_days
+        .map(|days| chrono::Local::now().naive_local() + chrono::Duration::days(days as i64));
+    let model = entity::api_key::ActiveModel {
+        user_id: Set(user.user_db.id),
+        name: Set(parm.name.clone()),
+        prefix: Set(prefix),
+        key_hash: Set(key_hash),
+        scopes: Set(serde_json::json!(scopes)),
+        expires_at: Set(expires_at),
+        ..Default::default()
+    };
+    let model = ApiKeyDao::insert(&state.db, model).await?;
+    Ok((model, key))
+}
+
+/// 校验 API Key 并解析为用户信息
+pub async fn authenticate(key: &str, state: &AppState) -> Result<UserInfo, Error> {
+    if !key.starts_with(constant::API_KEY_PREFIX) {
+        return Err(Error::NotLogin);
+    }
+    let model = ApiKeyDao::query_by_hash(&state.db, &digest::sha256_hex(key))
+        .await?
+        .ok_or(Error::NotLogin)?;
+    let now = chrono::Local::now().naive_local();
+    if model.expires_at.is_some_and(|t| t <= now) {
+        return Err(Error::NotLogin);
+    }
+    let user_db = dao::query_by_id::<entity::user::Entity>(&state.db, model.user_id)
+        .await?
+        .filter(|u| u.enable_flag == 1)
+        .ok_or(Error::NotLogin)?;
+    let permissions = PermissionDao::query_codes_by_role(&state.db, user_db.role).await?;
+
+    // 限制写库频率，避免高频调用时每个请求都更新
+    let stale = model
+        .last_used_at
+        .is_none_or(|t| (now - t).num_seconds() >= constant::API_KEY_TOUCH_SECS);
+    if stale {
+        ApiKeyDao::touch(&state.db, model.id).await?;
+    }
+
+    Ok(UserInfo {
+        token: String::new(),
+        user_db,
+        permissions,
+        session: SessionMeta {
+            id: format!("apikey:{}", model.id),
+            issued_at: model.created_at.unwrap_or(now),
+            last_seen: now,
+            ip: None,
+            user_agent: None,
+        },
+        refresh_token: None,
+        scopes: Some(scopes_of(&model)),
+        impersonator: None,
+    })
+}
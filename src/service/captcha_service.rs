@@ -0,0 +1,140 @@
+//! 登录图形验证码
+//!
+//! 本地生成 PNG 图片，答案存放于 Redis（`Captcha:{id}`，带过期时间）。
+//! 验证码只能校验一次，无论对错都立即作废
+
+use std::time::Duration;
+
+use captcha::{
+    Captcha,
+    filters::{Dots, Noise, Wave},
+};
+
+use crate::{
+    AppState, Error,
+    core::{
+        config::{CONFIG, CaptchaDifficulty},
+        constant,
+    },
+    service::login_service,
+    utils::{RedisUtil, random},
+    vo::user_vo,
+};
+
+fn captcha_key(id: &str) -> String {
+    format!("{}:{}", constant::CAPTCHA_KEY, id)
+}
+
+/// 按配置的难度生成验证码，返回 (答案, PNG base64)
+fn render() -> Result<(String, String), Error> {
+    let conf = &CONFIG.auth.captcha;
+    let mut captcha = Captcha::new();
+    captcha.add_chars(conf.length.clamp(1, 8));
+    match conf.difficulty {
+        CaptchaDifficulty::Easy => {
+            captcha.apply_filter(Noise::new(0.1));
+        }
+        CaptchaDifficulty::Medium => {
+            captcha
+                .apply_filter(Noise::new(0.3))
+                .apply_filter(Wave::new(2.0, 16.0).horizontal());
+        }
+        CaptchaDifficulty::Hard => {
+            captcha
+                .apply_filter(Noise::new(0.4))
+                .apply_filter(Wave::new(2.0, 20.0).horizontal())
+                .apply_filter(Wave::new(2.0, 20.0).vertical())
+                .apply_filter(Dots::new(15));
+        }
+    }
+    captcha.view(220, 120);
+    let image = captcha
+        .as_base64()
+        .ok_or_else(|| Error::unknown("生成验证码图片失败"))?;
+    Ok((captcha.chars_as_string(), image))
+}
+
+/// 生成新的验证码，同一 IP 每分钟的获取次数受限
+pub async fn generate(state: &AppState, ip: Option<&str>) -> Result<user_vo::CaptchaResp, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let limit = CONFIG.auth.captcha.rate_limit;
+    if let Some(ip) = ip.filter(|_| limit > 0) {
+        let rate_key = format!("{}:{}", constant::CAPTCHA_RATE_KEY, ip);
+        let count = redis
+            .incr_with_expire(&rate_key, Duration::from_secs(60))
+            .await?;
+        if count > limit as i64 {
+            let secs = redis.ttl(&rate_key).await?.unwrap_or(60).max(1);
+            return Err(Error::TooManyRequests(secs));
+        }
+    }
+
+    let (answer, image) = render()?;
+    let id = random::token(16);
+    let ttl = CONFIG.auth.captcha.ttl;
+    redis
+        .set_with_expire(
+            &captcha_key(&id),
+            answer.to_lowercase(),
+            Duration::from_secs(ttl),
+        )
+        .await?;
+    Ok(user_vo::CaptchaResp {
+        captcha_id: id,
+        image: format!("data:image/png;base64,{}", image),
+        expires_in: ttl,
+    })
+}
+
+/// 校验验证码（不区分大小写），校验后立即作废
+pub async fn verify(state: &AppState, id: &str, code: &str) -> Result<bool, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let answer = redis.take::<String>(&captcha_key(id)).await?;
+    Ok(answer.is_some_and(|answer| answer == code.trim().to_lowercase()))
+}
+
+/// 需要验证码时校验登录请求携带的验证码
+pub async fn check_login(
+    state: &AppState,
+    username: &str,
+    ip: Option<&str>,
+    captcha_id: Option<&str>,
+    captcha_code: Option<&str>,
+) -> Result<(), Error> {
+    if !CONFIG.auth.captcha.enabled || !login_service::captcha_required(state, username, ip).await?
+    {
+        return Ok(());
+    }
+    let (Some(id), Some(code)) = (captcha_id, captcha_code) else {
+        return Err(Error::CaptchaRequired("请输入验证码".into()));
+    };
+    if !verify(state, id, code).await? {
+        return Err(Error::CaptchaRequired("验证码错误或已过期".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_captcha {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let (answer, image) = render().unwrap();
+        assert_eq!(answer.chars().count(), CONFIG.auth.captcha.length as usize);
+        assert!(!image.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_generate_rate_limit() {
+        let state = AppState::mock(sea_orm::MockDatabase::new(sea_orm::DbBackend::MySql)).await;
+        let limit = CONFIG.auth.captcha.rate_limit;
+        for _ in 0..limit {
+            generate(&state, Some("10.0.0.1")).await.unwrap();
+        }
+        let res = generate(&state, Some("10.0.0.1")).await;
+        assert!(matches!(res, Err(Error::TooManyRequests(secs)) if secs <= 60));
+        // 其他 IP 不受影响
+        assert!(generate(&state, Some("10.0.0.2")).await.is_ok());
+    }
+}
@@ -0,0 +1,239 @@
+//! 管理员模拟登录
+//!
+//! 管理员以目标用户身份签发新的会话，会话中记录真实操作人（管理员），
+//! 管理员自己的会话保持不变。结束模拟时吊销模拟会话：会话模式下回到管理员原会话，
+//! JWT 模式下无法取回原 token，为管理员重新签发
+
+use sea_orm::EntityName;
+use tracing::info;
+
+use crate::{
+    AppState, Error,
+    core::{
+        config::{AuthMode, CONFIG},
+        constant,
+        extractor::{ClientInfo, Impersonator, UserInfo},
+        session,
+    },
+    dao::{self, AuditAction, AuditDao},
+    entity,
+};
+
+/// 开始模拟登录，返回目标用户的模拟会话
+pub async fn start(
+    state: &AppState,
+    admin: &UserInfo,
+    target_id: i64,
+    client: &ClientInfo,
+) -> Result<UserInfo, Error> {
+    if admin.is_api_key() {
+        return Err(Error::Unauthorized("API Key 不能模拟登录".into()));
+    }
+    if admin.is_impersonated() {
+        return Err(Error::InvalidState("请先结束当前模拟登录".into()));
+    }
+    // 路由权限之外再限定为管理员，避免其他角色被授予 user:impersonate 后可模拟
+    if !admin.is_admin() {
+        return Err(Error::Unauthorized("仅管理员可以模拟登录".into()));
+    }
+    if target_id == admin.user_db.id {
+        return Err(Error::InvalidState("不能模拟自己".into()));
+    }
+    let target = dao::query_by_id::<entity::user::Entity>(&state.db, target_id)
+        .await?
+        .ok_or_else(|| Error::NotFound("用户不存在".into()))?;
+    if target.enable_flag != 1 {
+        return Err(Error::InvalidState("账号已禁用".into()));
+    }
+    // 模拟管理员等同于共享其全部权限，不允许
+    if target.role == constant::ADMIN_ROLE {
+        return Err(Error::Unauthorized("不能模拟管理员".into()));
+    }
+
+    let impersonator = Impersonator {
+        user_id: admin.user_db.id,
+        username: admin.user_db.username.clone(),
+        session_id: admin.session.id.clone(),
+    };
+    AuditDao::record::<()>(
+        &state.db,
+        AuditAction::Impersonate,
+        entity::user::Entity.table_name(),
+        target.id,
+        None,
+        None,
+    )
+    .await;
+    UserInfo::impersonate(target, impersonator, client, state).await
+}
+
+/// 结束模拟登录，返回管理员的会话
+pub async fn stop(
+    state: &AppState,
+    user: &UserInfo,
+    client: &ClientInfo,
+) -> Result<UserInfo, Error> {
+    let Some(admin) = &user.impersonator else {
+        return Err(Error::InvalidState("当前未处于模拟登录".into()));
+    };
+    AuditDao::record::<()>(
+        &state.db,
+        AuditAction::ImpersonateStop,
+        entity::user::Entity.table_name(),
+        user.user_db.id,
+        None,
+        None,
+    )
+    .await;
+    user.logout(state).await?;
+    info!(
+        "Impersonation stopped: {} as {:?}",
+        admin.username, user.user_db.username
+    );
+
+    match CONFIG.auth.mode {
+        AuthMode::Session => {
+            let token = session::token_of(state, admin.user_id, &admin.session_id)
+                .await?
+                .ok_or(Error::NotLogin)?;
+            UserInfo::authenticate(&token, state).await
+        }
+        AuthMode::Jwt => {
+            let admin_db = dao::query_by_id::<entity::user::Entity>(&state.db, admin.user_id)
+                .await?
+                .filter(|u| u.enable_flag == 1)
+                .ok_or(Error::NotLogin)?;
+            UserInfo::login(admin_db, client, state).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_impersonation {
+    use super::*;
+    use sea_orm::{DbBackend, MockDatabase};
+
+    const ADMIN_ID: i64 = 1;
+    const USER_ID: i64 = 2;
+
+    fn user_db(id: i64, role: i32) -> entity::user::Model {
+        entity::user::Model {
+            id,
+            username: format!("user{}", id),
+            password: String::new(),
+            salt: None,
+            role,
+            enable_flag: 1,
+            must_change_password: 0,
+            locale: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn no_permissions() -> Vec<entity::role_permission::Model> {
+        Vec::new()
+    }
+
+    /// 管理员登录、模拟普通用户依次需要的查询结果
+    fn login_then_start() -> MockDatabase {
+        MockDatabase::new(DbBackend::MySql)
+            .append_query_results([no_permissions()])
+            .append_query_results([vec![user_db(USER_ID, 1)]])
+            .append_query_results([no_permissions()])
+    }
+
+    #[tokio::test]
+    async fn test_start_refused() {
+        let db = MockDatabase::new(DbBackend::MySql)
+            .append_query_results([vec![user_db(USER_ID, constant::ADMIN_ROLE)]]);
+        let state = AppState::mock(db).await;
+        let client = ClientInfo::default();
+        let admin = UserInfo {
+            token: String::from("admin"),
+            user_db: user_db(ADMIN_ID, constant::ADMIN_ROLE),
+            permissions: Vec::new(),
+            session: Default::default(),
+            refresh_token: None,
+            scopes: None,
+            impersonator: None,
+        };
+
+        let api_key = UserInfo {
+            scopes: Some(Vec::new()),
+            ..admin.clone()
+        };
+        let res = start(&state, &api_key, USER_ID, &client).await;
+        assert!(matches!(res, Err(Error::Unauthorized(_))));
+
+        let nested = UserInfo {
+            impersonator: Some(Impersonator {
+                user_id: 3,
+                username: String::from("user3"),
+                session_id: String::from("s3"),
+            }),
+            ..admin.clone()
+        };
+        let res = start(&state, &nested, USER_ID, &client).await;
+        assert!(matches!(res, Err(Error::InvalidState(_))));
+
+        // 非管理员即使拥有 user:impersonate 权限也不能模拟
+        let not_admin = UserInfo {
+            user_db: user_db(3, 1),
+            permissions: vec![String::from(constant::PERM_USER_IMPERSONATE)],
+            ..admin.clone()
+        };
+        let res = start(&state, &not_admin, USER_ID, &client).await;
+        assert!(matches!(res, Err(Error::Unauthorized(_))));
+
+        let res = start(&state, &admin, ADMIN_ID, &client).await;
+        assert!(matches!(res, Err(Error::InvalidState(_))));
+
+        let res = start(&state, &admin, USER_ID, &client).await;
+        assert!(matches!(res, Err(Error::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stop_restores_admin_session() {
+        let state = AppState::mock(login_then_start()).await;
+        let client = ClientInfo::default();
+        let admin = UserInfo::login(user_db(ADMIN_ID, constant::ADMIN_ROLE), &client, &state)
+            .await
+            .unwrap();
+
+        let impersonated = start(&state, &admin, USER_ID, &client).await.unwrap();
+        assert_eq!(impersonated.user_db.id, USER_ID);
+        assert_eq!(
+            impersonated.impersonator.as_ref().map(|a| a.user_id),
+            Some(ADMIN_ID)
+        );
+
+        let restored = stop(&state, &impersonated, &client).await.unwrap();
+        assert_eq!(restored.token, admin.token);
+        assert!(!restored.is_impersonated());
+        // 模拟会话已吊销
+        let res = UserInfo::authenticate(&impersonated.token, &state).await;
+        assert!(matches!(res, Err(Error::NotLogin)));
+    }
+
+    #[tokio::test]
+    async fn test_admin_revoke_ends_impersonation() {
+        let state = AppState::mock(login_then_start()).await;
+        let client = ClientInfo::default();
+        let admin = UserInfo::login(user_db(ADMIN_ID, constant::ADMIN_ROLE), &client, &state)
+            .await
+            .unwrap();
+        let impersonated = start(&state, &admin, USER_ID, &client).await.unwrap();
+        assert!(
+            UserInfo::authenticate(&impersonated.token, &state)
+                .await
+                .is_ok()
+        );
+
+        session::revoke(&state, ADMIN_ID, &admin.session.id)
+            .await
+            .unwrap();
+        let res = UserInfo::authenticate(&impersonated.token, &state).await;
+        assert!(matches!(res, Err(Error::NotLogin)));
+    }
+}
@@ -0,0 +1,262 @@
+//! 登录防暴力破解
+//!
+//! 分别按账号与客户端 IP 统计窗口内的失败次数，超过阈值后临时锁定，
+//! 连续锁定时锁定时长按指数退避增长。Redis 中的数据结构：
+//! - `LoginFail:{user|ip}:{name}`：窗口内失败次数
+//! - `LoginLock:{user|ip}:{name}`：锁定标记，过期即解锁
+//! - `LoginLockLevel:{user|ip}:{name}`：连续锁定次数，用于计算退避时长
+
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::{
+    AppState, Error,
+    core::{config::CONFIG, constant},
+    utils::RedisUtil,
+};
+
+/// 锁定维度
+#[derive(Clone, Copy)]
+enum Scope {
+    User,
+    Ip,
+}
+
+impl Scope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Scope::User => "user",
+            Scope::Ip => "ip",
+        }
+    }
+
+    fn max_attempts(&self) -> u32 {
+        match self {
+            Scope::User => CONFIG.auth.login.max_attempts,
+            Scope::Ip => CONFIG.auth.login.ip_max_attempts,
+        }
+    }
+}
+
+fn key(prefix: &str, scope: Scope, name: &str) -> String {
+    format!("{}:{}:{}", prefix, scope.as_str(), name)
+}
+
+/// 账号名不区分大小写，与数据库排序规则保持一致
+fn targets(username: &str, ip: Option<&str>) -> Vec<(Scope, String)> {
+    let mut targets = vec![(Scope::User, username.to_lowercase())];
+    if let Some(ip) = ip {
+        targets.push((Scope::Ip, ip.to_string()));
+    }
+    targets
+}
+
+/// 第 level 次锁定的时长：首次为 lock_secs，之后翻倍，不超过 max_lock_secs
+fn lock_duration(level: i64) -> u64 {
+    let conf = &CONFIG.auth.login;
+    let exp = level.saturating_sub(1).clamp(0, 32) as u32;
+    conf.lock_secs
+        .saturating_mul(2u64.saturating_pow(exp))
+        .min(conf.max_lock_secs)
+}
+
+/// 检查账号或 IP 是否处于锁定中
+pub async fn check_locked(state: &AppState, username: &str, ip: Option<&str>) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    for (scope, name) in targets(username, ip) {
+        if let Some(ttl) = redis
+            .ttl(&key(constant::LOGIN_LOCK_KEY, scope, &name))
+            .await?
+        {
+            return Err(Error::AccountLocked(ttl));
+        }
+    }
+    Ok(())
+}
+
+/// 账号或 IP 的失败次数达到验证码阈值，或近期被锁定过时，登录须提交图形验证码
+pub async fn captcha_required(
+    state: &AppState,
+    username: &str,
+    ip: Option<&str>,
+) -> Result<bool, Error> {
+    let threshold = CONFIG.auth.captcha.threshold as i64;
+    if threshold == 0 {
+        return Ok(true);
+    }
+    let redis = RedisUtil::new(state.redis.clone());
+    let targets = targets(username, ip);
+    let keys = targets
+        .iter()
+        .flat_map(|(scope, name)| {
+            [
+                key(constant::LOGIN_FAIL_KEY, *scope, name),
+                key(constant::LOGIN_LOCK_LEVEL_KEY, *scope, name),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let values = redis.mget::<i64>(&keys).await?;
+    // 锁定时失败计数会被重置，连续锁定次数仍在时同样要求验证码
+    Ok(values
+        .chunks(2)
+        .any(|pair| pair[0].is_some_and(|count| count >= threshold) || pair[1].is_some()))
+}
+
+/// 记录一次登录失败，达到阈值时锁定，返回本次触发的锁定时长
+pub async fn record_failure(
+    state: &AppState,
+    username: &str,
+    ip: Option<&str>,
+) -> Result<Option<u64>, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let conf = &CONFIG.auth.login;
+    let mut locked = None;
+    for (scope, name) in targets(username, ip) {
+        let fail_key = key(constant::LOGIN_FAIL_KEY, scope, &name);
+        let count = redis
+            .incr_with_expire(&fail_key, Duration::from_secs(conf.window_secs))
+            .await?;
+        if count < scope.max_attempts() as i64 {
+            continue;
+        }
+
+        // 达到阈值：锁定并重置计数，连续锁定次数每次锁定后保留两倍上限时长
+        let level_key = key(constant::LOGIN_LOCK_LEVEL_KEY, scope, &name);
+        let level = redis
+            .incr_and_expire(&level_key, Duration::from_secs(conf.max_lock_secs * 2))
+            .await?;
+        let secs = lock_duration(level);
+        redis
+            .set_with_expire(
+                &key(constant::LOGIN_LOCK_KEY, scope, &name),
+                level,
+                Duration::from_secs(secs),
+            )
+            .await?;
+        redis.del(&fail_key).await?;
+        warn!(
+            "Login locked: {}={}, level={}, secs={}",
+            scope.as_str(),
+            name,
+            level,
+            secs
+        );
+        locked = Some(locked.unwrap_or(0).max(secs));
+    }
+    Ok(locked)
+}
+
+/// 清除账号的失败计数、锁定与退避等级（登录成功或管理员解锁时调用）
+pub async fn unlock_user(state: &AppState, username: &str) -> Result<(), Error> {
+    unlock(state, Scope::User, &username.to_lowercase()).await
+}
+
+/// 管理员解除 IP 锁定
+pub async fn unlock_ip(state: &AppState, ip: &str) -> Result<(), Error> {
+    unlock(state, Scope::Ip, ip).await
+}
+
+async fn unlock(state: &AppState, scope: Scope, name: &str) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    let keys = [
+        constant::LOGIN_FAIL_KEY,
+        constant::LOGIN_LOCK_KEY,
+        constant::LOGIN_LOCK_LEVEL_KEY,
+    ]
+    .iter()
+    .map(|prefix| key(prefix, scope, name))
+    .collect::<Vec<_>>();
+    redis.del_many(&keys).await
+}
+
+#[cfg(test)]
+mod test_login {
+    use super::*;
+    use sea_orm::{DbBackend, MockDatabase};
+
+    async fn state() -> AppState {
+        AppState::mock(MockDatabase::new(DbBackend::MySql)).await
+    }
+
+    /// 连续失败 times 次，返回最后一次触发的锁定时长
+    async fn fail(state: &AppState, username: &str, ip: Option<&str>, times: u32) -> Option<u64> {
+        let mut locked = None;
+        for _ in 0..times {
+            locked = record_failure(state, username, ip).await.unwrap();
+        }
+        locked
+    }
+
+    fn locked_secs(res: Result<(), Error>) -> u64 {
+        match res {
+            Err(Error::AccountLocked(secs)) => secs,
+            other => panic!("expected AccountLocked, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lock_duration_backoff() {
+        let conf = &CONFIG.auth.login;
+        assert_eq!(lock_duration(1), conf.lock_secs);
+        assert_eq!(lock_duration(2), conf.lock_secs * 2);
+        assert_eq!(lock_duration(3), conf.lock_secs * 4);
+        assert_eq!(lock_duration(100), conf.max_lock_secs);
+    }
+
+    #[tokio::test]
+    async fn test_user_lock_and_backoff() {
+        let state = state().await;
+        let conf = &CONFIG.auth.login;
+        let ip = Some("10.0.0.1");
+
+        // 未达阈值不锁定
+        assert_eq!(fail(&state, "Alice", ip, conf.max_attempts - 1).await, None);
+        check_locked(&state, "alice", ip).await.unwrap();
+
+        // 达到阈值锁定账号，账号名不区分大小写，剩余时长用于 Retry-After
+        assert_eq!(fail(&state, "alice", ip, 1).await, Some(conf.lock_secs));
+        let secs = locked_secs(check_locked(&state, "ALICE", None).await);
+        assert!(secs > 0 && secs <= conf.lock_secs);
+        // 同 IP 的其他账号不受影响
+        check_locked(&state, "bob", ip).await.unwrap();
+
+        // 再次锁定时长翻倍
+        assert_eq!(
+            fail(&state, "alice", ip, conf.max_attempts).await,
+            Some(conf.lock_secs * 2)
+        );
+
+        // 解锁后清除锁定与退避等级
+        unlock_user(&state, "Alice").await.unwrap();
+        check_locked(&state, "alice", ip).await.unwrap();
+        assert_eq!(
+            fail(&state, "alice", ip, conf.max_attempts).await,
+            Some(conf.lock_secs)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ip_lock() {
+        let state = state().await;
+        let conf = &CONFIG.auth.login;
+        let ip = Some("10.0.0.2");
+
+        // 轮换账号名，单个账号不达阈值，按 IP 锁定
+        let mut locked = None;
+        for i in 0..conf.ip_max_attempts {
+            locked = record_failure(&state, &format!("user{}", i), ip)
+                .await
+                .unwrap();
+        }
+        assert_eq!(locked, Some(conf.lock_secs));
+        let secs = locked_secs(check_locked(&state, "carol", ip).await);
+        assert!(secs > 0 && secs <= conf.lock_secs);
+        check_locked(&state, "carol", Some("10.0.0.3"))
+            .await
+            .unwrap();
+
+        unlock_ip(&state, "10.0.0.2").await.unwrap();
+        check_locked(&state, "carol", ip).await.unwrap();
+    }
+}
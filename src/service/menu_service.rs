@@ -1,7 +1,14 @@
+use std::collections::{HashMap, HashSet};
+
 use sea_orm::DbConn;
 use serde::Serialize;
 
-use crate::{Error, dao, entity, vo};
+use crate::{
+    Error,
+    core::extractor::UserInfo,
+    dao::{self, MenuDao},
+    entity, vo,
+};
 
 #[derive(Debug, Serialize)]
 pub struct MenuNode {
@@ -18,6 +25,39 @@ pub async fn get_menu_tree(db: &DbConn, parm: &vo::QueryReq) -> Result<Vec<MenuN
     Ok(tree)
 }
 
+/// 获取当前用户角色可见的菜单树，管理员可见全部菜单
+pub async fn get_user_menu_tree(db: &DbConn, user: &UserInfo) -> Result<Vec<MenuNode>, Error> {
+    let all_menus = MenuDao::query_all_sorted(db).await?;
+    if user.is_admin() {
+        return Ok(build_tree(&all_menus, 0));
+    }
+    let allowed = MenuDao::query_ids_by_role(db, user.user_db.role).await?;
+    let visible = filter_visible(all_menus, &allowed);
+    Ok(build_tree(&visible, 0))
+}
+
+/// 过滤出授权菜单，并补全其祖先节点，避免子菜单因父菜单未授权而丢失
+fn filter_visible(
+    all_menus: Vec<entity::menu::Model>,
+    allowed: &[i64],
+) -> Vec<entity::menu::Model> {
+    let parent_of: HashMap<i64, i64> = all_menus.iter().map(|m| (m.id, m.parent_id)).collect();
+    let mut visible = HashSet::new();
+    for &id in allowed {
+        let mut current = id;
+        while let Some(&parent_id) = parent_of.get(&current) {
+            if !visible.insert(current) {
+                break;
+            }
+            current = parent_id;
+        }
+    }
+    all_menus
+        .into_iter()
+        .filter(|m| visible.contains(&m.id))
+        .collect()
+}
+
 fn build_tree(all_menus: &[entity::menu::Model], parent_id: i64) -> Vec<MenuNode> {
     all_menus
         .iter()
@@ -30,3 +70,38 @@ fn build_tree(all_menus: &[entity::menu::Model], parent_id: i64) -> Vec<MenuNode
         })
         .collect()
 }
+
+#[cfg(test)]
+mod test_menu {
+    use super::*;
+
+    fn menu(id: i64, parent_id: i64) -> entity::menu::Model {
+        entity::menu::Model {
+            id,
+            parent_id,
+            path: format!("/m{}", id),
+            name: format!("m{}", id),
+            component: String::new(),
+            redirect: None,
+            sort: 0,
+            meta: None,
+            status: 1,
+            remark: None,
+            create_time: chrono::NaiveDateTime::default(),
+            update_time: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_filter_visible_keeps_ancestors() {
+        // 1 -> 2 -> 3, 4 为独立根菜单
+        let all = vec![menu(1, 0), menu(2, 1), menu(3, 2), menu(4, 0)];
+        let visible = filter_visible(all, &[3, 99]);
+        let ids: Vec<i64> = visible.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let tree = build_tree(&visible, 0);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].children[0].children[0].id, 3);
+    }
+}
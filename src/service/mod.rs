@@ -1 +1,8 @@
+pub mod api_key_service;
+pub mod captcha_service;
+pub mod impersonation_service;
+pub mod login_service;
 pub mod menu_service;
+pub mod oidc_service;
+pub mod password_service;
+pub mod totp_service;
@@ -0,0 +1,242 @@
+//! 密码修改与重置
+//!
+//! - 新密码须满足配置的密码策略，且不能与当前及最近 N 次使用过的密码相同
+//! - 管理员重置：签发一次性令牌并要求下次登录后修改密码，该用户的全部会话立即失效
+//! - Redis 中只保存令牌摘要：`PasswordReset:{sha256(token)}` -> user_id，
+//!   `PasswordResetUser:{user_id}` -> 摘要；每个用户只保留最新的令牌，设置新密码后作废
+
+use std::time::Duration;
+
+use validator::{ValidationError, ValidationErrors};
+
+use crate::{
+    AppState, Error,
+    core::{config::CONFIG, constant, session},
+    dao::{PasswordHistoryDao, UserDao},
+    entity,
+    utils::{RedisUtil, digest, password, random},
+};
+
+/// 检查密码策略，返回全部不满足的规则
+fn policy_violations(plain: &str) -> Vec<String> {
+    let conf = &CONFIG.auth.password;
+    let mut violations = Vec::new();
+    let len = plain.chars().count();
+    if len < conf.min_length || len > conf.max_length {
+        violations.push(format!(
+            "密码长度必须在{}到{}个字符之间",
+            conf.min_length, conf.max_length
+        ));
+    }
+    let rules = [
+        (
+            conf.require_upper,
+            plain.chars().any(|c| c.is_uppercase()),
+            "大写字母",
+        ),
+        (
+            conf.require_lower,
+            plain.chars().any(|c| c.is_lowercase()),
+            "小写字母",
+        ),
+        (
+            conf.require_digit,
+            plain.chars().any(|c| c.is_ascii_digit()),
+            "数字",
+        ),
+        (
+            conf.require_symbol,
+            plain.chars().any(|c| !c.is_alphanumeric()),
+            "特殊字符",
+        ),
+    ];
+    for (required, satisfied, name) in rules {
+        if required && !satisfied {
+            violations.push(format!("密码须包含{}", name));
+        }
+    }
+    violations
+}
+
+fn invalid(field: &'static str, messages: Vec<String>) -> Error {
+    let mut errors = ValidationErrors::new();
+    for message in messages {
+        errors.add(
+            field,
+            ValidationError::new("password_policy").with_message(message.into()),
+        );
+    }
+    Error::InvalidParameter(errors)
+}
+
+/// 校验新密码是否满足密码策略，`field` 为请求中的字段名
+pub fn check_policy(plain: &str, field: &'static str) -> Result<(), Error> {
+    let violations = policy_violations(plain);
+    if !violations.is_empty() {
+        return Err(invalid(field, violations));
+    }
+    Ok(())
+}
+
+/// 校验新密码：密码策略 + 不与当前及最近 N 次的密码相同
+pub async fn check_new_password(
+    db: &sea_orm::DbConn,
+    user_db: &entity::user::Model,
+    plain: &str,
+    field: &'static str,
+) -> Result<(), Error> {
+    check_policy(plain, field)?;
+    let history = CONFIG.auth.password.history;
+    if history == 0 {
+        return Ok(());
+    }
+    let mut hashes = PasswordHistoryDao::query_recent(db, user_db.id, history as u64).await?;
+    hashes.push(user_db.password.clone());
+    for hash in &hashes {
+        if password::verify(plain, hash).await? {
+            return Err(invalid(
+                field,
+                vec![format!("不能与最近 {} 次使用过的密码相同", history)],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 记录新密码到历史，只保留策略需要的条数
+pub async fn record_history(
+    db: &sea_orm::DbConn,
+    user_id: i64,
+    password_hash: &str,
+) -> Result<(), Error> {
+    PasswordHistoryDao::insert(db, user_id, password_hash).await?;
+    PasswordHistoryDao::prune(db, user_id, CONFIG.auth.password.history as u64).await
+}
+
+/// 设置新密码（调用方需先校验），同时清除“须修改密码”标记并作废未使用的重置令牌
+pub async fn set_password(
+    state: &AppState,
+    user_id: i64,
+    plain: &str,
+) -> Result<entity::user::Model, Error> {
+    let hashed = password::hash(plain).await?;
+    let user = UserDao::change_password(&state.db, user_id, &hashed, 0).await?;
+    record_history(&state.db, user_id, &hashed).await?;
+    revoke_reset(state, user_id).await?;
+    Ok(user)
+}
+
+fn reset_key(token_digest: &str) -> String {
+    format!("{}:{}", constant::PASSWORD_RESET_KEY, token_digest)
+}
+
+fn user_reset_key(user_id: i64) -> String {
+    format!("{}:{}", constant::PASSWORD_RESET_USER_KEY, user_id)
+}
+
+/// 作废用户尚未使用的重置令牌
+pub async fn revoke_reset(state: &AppState, user_id: i64) -> Result<(), Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    if let Some(token_digest) = redis.take::<String>(&user_reset_key(user_id)).await? {
+        redis.del(&reset_key(&token_digest)).await?;
+    }
+    Ok(())
+}
+
+/// 管理员重置密码：要求下次登录后修改密码，吊销全部会话，返回一次性令牌。
+/// 此前签发的令牌随之作废
+pub async fn issue_reset(state: &AppState, user_id: i64) -> Result<String, Error> {
+    revoke_reset(state, user_id).await?;
+    let token = random::token(constant::TOKEN_BYTES);
+    let token_digest = digest::sha256_hex(&token);
+    let ttl = Duration::from_secs(CONFIG.auth.password.reset_ttl);
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .set_with_expire(&reset_key(&token_digest), user_id, ttl)
+        .await?;
+    redis
+        .set_with_expire(&user_reset_key(user_id), token_digest, ttl)
+        .await?;
+    UserDao::set_must_change_password(&state.db, user_id, 1).await?;
+    session::revoke_user(state, user_id).await?;
+    Ok(token)
+}
+
+/// 查询重置令牌对应的用户（不消费）
+pub async fn peek_reset(state: &AppState, token: &str) -> Result<i64, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .get::<i64>(&reset_key(&digest::sha256_hex(token)))
+        .await?
+        .ok_or_else(|| Error::InvalidCredentials("重置令牌无效或已过期".into()))
+}
+
+/// 消费重置令牌，已被使用时返回 false
+pub async fn consume_reset(state: &AppState, token: &str) -> Result<bool, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    Ok(redis
+        .take::<i64>(&reset_key(&digest::sha256_hex(token)))
+        .await?
+        .is_some())
+}
+
+#[cfg(test)]
+mod test_password_policy {
+    use super::*;
+    use sea_orm::{DbBackend, MockDatabase, MockExecResult};
+
+    #[test]
+    fn test_policy_violations() {
+        let conf = &CONFIG.auth.password;
+        assert!(!policy_violations("a").is_empty());
+
+        // 满足全部字符类别，长度补足到下限
+        let mut valid = String::from("Aa1!");
+        while valid.chars().count() < conf.min_length {
+            valid.push('a');
+        }
+        assert!(policy_violations(&valid).is_empty());
+
+        let too_long = "Aa1!".repeat(conf.max_length);
+        assert_eq!(policy_violations(&too_long).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_single_reset_token() {
+        let user = entity::user::Model {
+            id: 2,
+            username: String::from("user2"),
+            password: String::new(),
+            salt: None,
+            role: 1,
+            enable_flag: 1,
+            must_change_password: 1,
+            locale: None,
+            created_at: None,
+            updated_at: None,
+        };
+        // 每次签发都会更新“须修改密码”标记：更新前查询、UPDATE、更新后回查
+        let mut db = MockDatabase::new(DbBackend::MySql);
+        for _ in 0..2 {
+            db = db
+                .append_query_results([vec![user.clone()], vec![user.clone()]])
+                .append_exec_results([MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                }]);
+        }
+        let state = AppState::mock(db).await;
+
+        let first = issue_reset(&state, user.id).await.unwrap();
+        assert_eq!(peek_reset(&state, &first).await.unwrap(), user.id);
+        // 再次重置后旧令牌失效
+        let second = issue_reset(&state, user.id).await.unwrap();
+        let res = peek_reset(&state, &first).await;
+        assert!(matches!(res, Err(Error::InvalidCredentials(_))));
+        assert_eq!(peek_reset(&state, &second).await.unwrap(), user.id);
+
+        // 用户自行设置新密码后未使用的令牌同样失效
+        revoke_reset(&state, user.id).await.unwrap();
+        assert!(!consume_reset(&state, &second).await.unwrap());
+    }
+}
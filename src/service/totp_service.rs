@@ -0,0 +1,232 @@
+//! TOTP 两步验证
+//!
+//! - 绑定：生成密钥与恢复码（待确认），用户在验证器 App 中扫码后提交一次验证码完成启用
+//! - 登录：密码校验通过后，若已启用两步验证（或管理员被要求启用），先签发短期的待验证令牌，
+//!   验证码或恢复码校验通过后才签发正式会话
+//! - 同一时间步的验证码只能使用一次，恢复码使用后作废
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    AppState, Error,
+    core::{config::CONFIG, constant},
+    dao::UserTotpDao,
+    entity,
+    utils::{RedisUtil, digest, random},
+    vo::user_vo,
+};
+
+/// 时间步长 秒
+const STEP_SECS: u64 = 30;
+
+/// 待两步验证的登录
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PendingLogin {
+    pub user_id: i64,
+    /// 是否需要先完成绑定
+    pub enroll: bool,
+}
+
+fn pending_key(token: &str) -> String {
+    format!("{}:{}", constant::TOTP_PENDING_KEY, token)
+}
+
+fn build_totp(secret: &str, account: &str) -> Result<TOTP, Error> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| Error::InvalidState(format!("TOTP 密钥无效:{:?}", e)))?;
+    // 签发方与账号名中不允许出现 ':'
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        STEP_SECS,
+        bytes,
+        Some(CONFIG.auth.totp.issuer.replace(':', "_")),
+        account.replace(':', "_"),
+    )
+    .map_err(|e| Error::InvalidState(format!("TOTP 参数无效:{:?}", e)))
+}
+
+/// 校验验证码，允许前后各一个时间步的偏差；已使用过的时间步不再接受。返回匹配的时间步
+fn match_step(totp: &TOTP, code: &str, now: u64, last_used_step: i64) -> Option<i64> {
+    let current = (now / STEP_SECS) as i64;
+    (current - 1..=current + 1)
+        .filter(|step| *step > last_used_step)
+        .find(|step| totp.check(code, *step as u64 * STEP_SECS))
+}
+
+fn now_secs() -> u64 {
+    chrono::Local::now().timestamp() as u64
+}
+
+/// 恢复码统一为小写、去掉分隔符后再计算摘要
+fn recovery_hash(code: &str) -> String {
+    digest::sha256_hex(&code.trim().to_lowercase().replace('-', ""))
+}
+
+/// 生成恢复码，返回 (明文列表, 摘要列表)
+fn generate_recovery_codes() -> (Vec<String>, Value) {
+    let codes: Vec<String> = (0..constant::TOTP_RECOVERY_CODES)
+        .map(|_| {
+            let token = random::token(5);
+            format!("{}-{}", &token[..5], &token[5..])
+        })
+        .collect();
+    let hashes: Vec<String> = codes.iter().map(|c| recovery_hash(c)).collect();
+    (codes, serde_json::json!(hashes))
+}
+
+/// 使用恢复码，命中时返回剩余的摘要列表
+fn consume_recovery_code(hashes: &Value, code: &str) -> Option<Value> {
+    let mut hashes: Vec<String> = serde_json::from_value(hashes.clone()).unwrap_or_default();
+    let hash = recovery_hash(code);
+    let index = hashes.iter().position(|h| *h == hash)?;
+    hashes.remove(index);
+    Some(serde_json::json!(hashes))
+}
+
+/// 生成新的密钥与恢复码（待确认），已启用时需先停用
+pub async fn begin_enroll(
+    db: &sea_orm::DbConn,
+    user_db: &entity::user::Model,
+) -> Result<user_vo::TotpEnrollResp, Error> {
+    if let Some(existing) = UserTotpDao::query_by_user(db, user_db.id).await?
+        && existing.enabled == 1
+    {
+        return Err(Error::AlreadyExists("已启用两步验证".into()));
+    }
+    let secret = Secret::generate_secret().to_encoded().to_string();
+    let totp = build_totp(&secret, &user_db.username)?;
+    let (recovery_codes, hashes) = generate_recovery_codes();
+    UserTotpDao::upsert_pending(db, user_db.id, &secret, hashes).await?;
+    Ok(user_vo::TotpEnrollResp {
+        otpauth_uri: totp.get_url(),
+        secret,
+        recovery_codes,
+    })
+}
+
+/// 提交首个验证码，确认绑定
+pub async fn activate(
+    db: &sea_orm::DbConn,
+    user_db: &entity::user::Model,
+    code: &str,
+) -> Result<bool, Error> {
+    let row = UserTotpDao::query_by_user(db, user_db.id)
+        .await?
+        .ok_or_else(|| Error::InvalidState("请先生成两步验证密钥".into()))?;
+    if row.enabled == 1 {
+        return Err(Error::AlreadyExists("已启用两步验证".into()));
+    }
+    let totp = build_totp(&row.secret, &user_db.username)?;
+    let Some(step) = match_step(&totp, code, now_secs(), row.last_used_step) else {
+        return Ok(false);
+    };
+    UserTotpDao::update_state(db, user_db.id, 1, step, row.recovery_codes).await?;
+    Ok(true)
+}
+
+/// 校验已启用用户的验证码或恢复码
+pub async fn verify(
+    db: &sea_orm::DbConn,
+    user_db: &entity::user::Model,
+    code: &str,
+) -> Result<bool, Error> {
+    let row = UserTotpDao::query_by_user(db, user_db.id)
+        .await?
+        .filter(|r| r.enabled == 1)
+        .ok_or_else(|| Error::InvalidState("未启用两步验证".into()))?;
+    let totp = build_totp(&row.secret, &user_db.username)?;
+    if let Some(step) = match_step(&totp, code, now_secs(), row.last_used_step) {
+        UserTotpDao::update_state(db, user_db.id, 1, step, row.recovery_codes).await?;
+        return Ok(true);
+    }
+    if let Some(remaining) = consume_recovery_code(&row.recovery_codes, code) {
+        UserTotpDao::update_state(db, user_db.id, 1, row.last_used_step, remaining).await?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// 密码校验通过后判断是否需要两步验证，需要时签发待验证令牌
+pub async fn challenge(
+    state: &AppState,
+    user_db: &entity::user::Model,
+) -> Result<Option<user_vo::TwoFactorResp>, Error> {
+    let enabled = UserTotpDao::query_by_user(&state.db, user_db.id)
+        .await?
+        .is_some_and(|r| r.enabled == 1);
+    let enroll =
+        !enabled && CONFIG.auth.totp.require_for_admin && user_db.role == constant::ADMIN_ROLE;
+    if !enabled && !enroll {
+        return Ok(None);
+    }
+
+    let token = random::token(constant::TOKEN_BYTES);
+    let pending = PendingLogin {
+        user_id: user_db.id,
+        enroll,
+    };
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .set_with_expire(
+            &pending_key(&token),
+            serde_json::to_string(&pending).unwrap(),
+            Duration::from_secs(CONFIG.auth.totp.pending_ttl),
+        )
+        .await?;
+    Ok(Some(user_vo::TwoFactorResp {
+        pending_token: token,
+        enroll,
+        expires_in: CONFIG.auth.totp.pending_ttl,
+    }))
+}
+
+/// 读取待验证的登录
+pub async fn pending(state: &AppState, token: &str) -> Result<PendingLogin, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    redis
+        .get::<String>(&pending_key(token))
+        .await?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .ok_or_else(|| Error::InvalidCredentials("登录已失效，请重新登录".into()))
+}
+
+/// 两步验证完成，作废待验证令牌；已被并发请求消费时返回 false
+pub async fn complete(state: &AppState, token: &str) -> Result<bool, Error> {
+    let redis = RedisUtil::new(state.redis.clone());
+    Ok(redis.take::<String>(&pending_key(token)).await?.is_some())
+}
+
+#[cfg(test)]
+mod test_totp {
+    use super::*;
+
+    #[test]
+    fn test_match_step_rejects_replay() {
+        let secret = Secret::generate_secret().to_encoded().to_string();
+        let totp = build_totp(&secret, "admin").unwrap();
+        let now = 1_700_000_000;
+        let code = totp.generate(now);
+        let step = match_step(&totp, &code, now, 0).unwrap();
+        assert_eq!(step, (now / STEP_SECS) as i64);
+        // 同一时间步的验证码不能再次使用
+        assert_eq!(match_step(&totp, &code, now, step), None);
+        // 超出允许偏差的验证码无效
+        assert_eq!(match_step(&totp, &code, now + STEP_SECS * 3, 0), None);
+    }
+
+    #[test]
+    fn test_recovery_code_single_use() {
+        let (codes, hashes) = generate_recovery_codes();
+        assert_eq!(codes.len(), constant::TOTP_RECOVERY_CODES);
+        let remaining = consume_recovery_code(&hashes, &codes[0].to_uppercase()).unwrap();
+        assert!(consume_recovery_code(&remaining, &codes[0]).is_none());
+        assert!(consume_recovery_code(&remaining, &codes[1].replace('-', "")).is_some());
+    }
+}
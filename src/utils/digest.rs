@@ -0,0 +1,29 @@
+//! 摘要工具
+
+use sha2::{Digest, Sha256};
+
+/// 计算 SHA-256 摘要
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    Sha256::digest(data).to_vec()
+}
+
+/// 计算 SHA-256 摘要（十六进制）
+pub fn sha256_hex(data: &str) -> String {
+    sha256(data.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_digest {
+    use super::*;
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! 测试用的内存 Redis
+//!
+//! 在本地端口上实现 RESP 协议中项目用到的命令（字符串、哈希、计数与有效期），
+//! 供需要 `AppState` 的单元测试使用，不依赖外部 Redis 服务。
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bb8::Pool;
+use bb8_redis::RedisConnectionManager;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+enum Entry {
+    Str(Vec<u8>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+}
+
+#[derive(Default)]
+struct Store {
+    data: HashMap<Vec<u8>, (Entry, Option<Instant>)>,
+}
+
+impl Store {
+    /// 取值前清理已过期的 key
+    fn entry(&mut self, key: &[u8]) -> Option<&mut (Entry, Option<Instant>)> {
+        if self
+            .data
+            .get(key)
+            .is_some_and(|(_, at)| at.is_some_and(|at| at <= Instant::now()))
+        {
+            self.data.remove(key);
+        }
+        self.data.get_mut(key)
+    }
+
+    fn hash(&mut self, key: &[u8]) -> &mut HashMap<Vec<u8>, Vec<u8>> {
+        if !matches!(self.entry(key), Some((Entry::Hash(_), _))) {
+            self.data
+                .insert(key.to_vec(), (Entry::Hash(HashMap::new()), None));
+        }
+        match self.data.get_mut(key) {
+            Some((Entry::Hash(hash), _)) => hash,
+            _ => unreachable!(),
+        }
+    }
+
+    fn exec(&mut self, args: &[Vec<u8>]) -> Reply {
+        let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+        let arg = |i: usize| args.get(i).map(Vec::as_slice).unwrap_or_default();
+        let int = |i: usize| {
+            String::from_utf8_lossy(arg(i))
+                .parse::<i64>()
+                .unwrap_or_default()
+        };
+        match name.as_str() {
+            "PING" => Reply::Status("PONG"),
+            "SET" => {
+                let mut expire = None;
+                let (mut nx, mut xx) = (false, false);
+                let mut i = 3;
+                while i < args.len() {
+                    match String::from_utf8_lossy(arg(i)).to_uppercase().as_str() {
+                        "NX" => nx = true,
+                        "XX" => xx = true,
+                        "EX" => {
+                            expire = Some(Duration::from_secs(int(i + 1) as u64));
+                            i += 1;
+                        }
+                        "PX" => {
+                            expire = Some(Duration::from_millis(int(i + 1) as u64));
+                            i += 1;
+                        }
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                let exists = self.entry(arg(1)).is_some();
+                if (nx && exists) || (xx && !exists) {
+                    return Reply::Nil;
+                }
+                let at = expire.map(|d| Instant::now() + d);
+                self.data
+                    .insert(arg(1).to_vec(), (Entry::Str(arg(2).to_vec()), at));
+                Reply::Status("OK")
+            }
+            "SETEX" => {
+                let at = Instant::now() + Duration::from_secs(int(2) as u64);
+                self.data
+                    .insert(arg(1).to_vec(), (Entry::Str(arg(3).to_vec()), Some(at)));
+                Reply::Status("OK")
+            }
+            "GET" | "GETDEL" => {
+                let value = match self.entry(arg(1)) {
+                    Some((Entry::Str(v), _)) => Reply::Bulk(v.clone()),
+                    _ => Reply::Nil,
+                };
+                if name == "GETDEL" {
+                    self.data.remove(arg(1));
+                }
+                value
+            }
+            "MGET" => Reply::Array(
+                args[1..]
+                    .iter()
+                    .map(|key| match self.entry(key) {
+                        Some((Entry::Str(v), _)) => Reply::Bulk(v.clone()),
+                        _ => Reply::Nil,
+                    })
+                    .collect(),
+            ),
+            "DEL" => {
+                let removed = args[1..]
+                    .iter()
+                    .filter(|key| self.entry(key).is_some() && self.data.remove(*key).is_some())
+                    .count();
+                Reply::Int(removed as i64)
+            }
+            "INCR" | "INCRBY" => {
+                let delta = if name == "INCR" { 1 } else { int(2) };
+                let count = match self.entry(arg(1)) {
+                    Some((Entry::Str(v), _)) => String::from_utf8_lossy(v).parse().unwrap_or(0),
+                    _ => 0,
+                } + delta;
+                let at = self.entry(arg(1)).and_then(|(_, at)| *at);
+                self.data.insert(
+                    arg(1).to_vec(),
+                    (Entry::Str(count.to_string().into_bytes()), at),
+                );
+                Reply::Int(count)
+            }
+            "EXPIRE" => match self.entry(arg(1)) {
+                Some((_, at)) => {
+                    *at = Some(Instant::now() + Duration::from_secs(int(2) as u64));
+                    Reply::Int(1)
+                }
+                None => Reply::Int(0),
+            },
+            "TTL" => match self.entry(arg(1)) {
+                Some((_, Some(at))) => {
+                    Reply::Int(at.saturating_duration_since(Instant::now()).as_secs() as i64)
+                }
+                Some((_, None)) => Reply::Int(-1),
+                None => Reply::Int(-2),
+            },
+            "HSET" => {
+                let hash = self.hash(arg(1));
+                let added = args[2..]
+                    .chunks(2)
+                    .filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none())
+                    .count();
+                Reply::Int(added as i64)
+            }
+            "HGET" => match self.entry(arg(1)) {
+                Some((Entry::Hash(hash), _)) => {
+                    hash.get(arg(2)).cloned().map_or(Reply::Nil, Reply::Bulk)
+                }
+                _ => Reply::Nil,
+            },
+            "HGETALL" => match self.entry(arg(1)) {
+                Some((Entry::Hash(hash), _)) => Reply::Array(
+                    hash.iter()
+                        .flat_map(|(k, v)| [Reply::Bulk(k.clone()), Reply::Bulk(v.clone())])
+                        .collect(),
+                ),
+                _ => Reply::Array(Vec::new()),
+            },
+            "HDEL" => match self.entry(arg(1)) {
+                Some((Entry::Hash(hash), _)) => {
+                    let removed = args[2..]
+                        .iter()
+                        .filter(|field| hash.remove(*field).is_some())
+                        .count();
+                    Reply::Int(removed as i64)
+                }
+                _ => Reply::Int(0),
+            },
+            // 连接初始化等其余命令直接应答
+            _ => Reply::Status("OK"),
+        }
+    }
+}
+
+enum Reply {
+    Status(&'static str),
+    Int(i64),
+    Bulk(Vec<u8>),
+    Nil,
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Reply::Status(s) => out.extend_from_slice(format!("+{}\r\n", s).as_bytes()),
+            Reply::Int(n) => out.extend_from_slice(format!(":{}\r\n", n).as_bytes()),
+            Reply::Bulk(v) => {
+                out.extend_from_slice(format!("${}\r\n", v.len()).as_bytes());
+                out.extend_from_slice(v);
+                out.extend_from_slice(b"\r\n");
+            }
+            Reply::Nil => out.extend_from_slice(b"$-1\r\n"),
+            Reply::Array(items) => {
+                out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                for item in items {
+                    item.write(out);
+                }
+            }
+        }
+    }
+}
+
+/// 启动内存 Redis 并返回连接池
+pub async fn pool() -> Pool<RedisConnectionManager> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let store = Arc::new(Mutex::new(Store::default()));
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, store.clone()));
+        }
+    });
+    let manager = RedisConnectionManager::new(format!("redis://{}", addr)).unwrap();
+    Pool::builder().max_size(2).build(manager).await.unwrap()
+}
+
+async fn serve(stream: TcpStream, store: Arc<Mutex<Store>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    // MULTI 之后的命令先排队，EXEC 时一并执行
+    let mut queued: Option<Vec<Vec<Vec<u8>>>> = None;
+    while let Some(args) = read_command(&mut reader).await {
+        let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+        let reply = match (name.as_str(), queued.as_mut()) {
+            ("MULTI", _) => {
+                queued = Some(Vec::new());
+                Reply::Status("OK")
+            }
+            ("EXEC", Some(_)) => {
+                let commands = queued.take().unwrap_or_default();
+                let mut store = store.lock().unwrap();
+                Reply::Array(commands.iter().map(|c| store.exec(c)).collect())
+            }
+            (_, Some(commands)) => {
+                commands.push(args);
+                Reply::Status("QUEUED")
+            }
+            _ => store.lock().unwrap().exec(&args),
+        };
+        let mut out = Vec::new();
+        reply.write(&mut out);
+        if writer.write_all(&out).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// 读取一条以数组形式发送的命令
+async fn read_command<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Option<Vec<Vec<u8>>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok().filter(|n| *n > 0)?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut buf = vec![0; len + 2];
+        reader.read_exact(&mut buf).await.ok()?;
+        buf.truncate(len);
+        args.push(buf);
+    }
+    Some(args)
+}
@@ -1,3 +1,8 @@
+pub mod digest;
+#[cfg(test)]
+pub mod mock_redis;
+pub mod password;
+pub mod random;
 mod redis;
 
 pub use redis::{RedisLock, RedisUtil};
@@ -0,0 +1,82 @@
+//! 密码哈希工具
+//!
+//! 使用 Argon2id 生成 PHC 格式字符串（盐值与参数均内嵌在字符串中），
+//! 兼容历史遗留的明文密码，便于登录时平滑升级。
+
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{self, SaltString},
+};
+use rand::RngCore;
+
+use crate::Error;
+
+/// PHC 字符串前缀，用于区分哈希值与历史明文
+const PHC_PREFIX: &str = "$argon2";
+
+/// 判断数据库中存储的值是否已是 Argon2 哈希
+pub fn is_hashed(stored: &str) -> bool {
+    stored.starts_with(PHC_PREFIX)
+}
+
+/// 生成密码哈希（PHC 字符串）
+pub async fn hash(plain: &str) -> Result<String, Error> {
+    let plain = plain.to_string();
+    tokio::task::spawn_blocking(move || hash_blocking(&plain))
+        .await
+        .map_err(|e| Error::unknown("密码哈希任务失败").with_source(e))?
+}
+
+/// 校验密码，存储值为明文时按明文比较（仅用于兼容旧数据）
+pub async fn verify(plain: &str, stored: &str) -> Result<bool, Error> {
+    if !is_hashed(stored) {
+        return Ok(plain == stored);
+    }
+    let plain = plain.to_string();
+    let stored = stored.to_string();
+    tokio::task::spawn_blocking(move || verify_blocking(&plain, &stored))
+        .await
+        .map_err(|e| Error::unknown("密码校验任务失败").with_source(e))?
+}
+
+fn hash_blocking(plain: &str) -> Result<String, Error> {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    let salt = SaltString::encode_b64(&bytes)
+        .map_err(|e| Error::unknown("生成盐值失败").with_source(e.to_string()))?;
+    let hash = Argon2::default()
+        .hash_password(plain.as_bytes(), &salt)
+        .map_err(|e| Error::unknown("密码哈希失败").with_source(e.to_string()))?;
+    Ok(hash.to_string())
+}
+
+fn verify_blocking(plain: &str, stored: &str) -> Result<bool, Error> {
+    let parsed = PasswordHash::new(stored)
+        .map_err(|e| Error::unknown("密码哈希格式错误").with_source(e.to_string()))?;
+    match Argon2::default().verify_password(plain.as_bytes(), &parsed) {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(Error::unknown("密码校验失败").with_source(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test_password {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hash_and_verify() {
+        let hashed = hash("secret123").await.unwrap();
+        assert!(is_hashed(&hashed));
+        assert!(hashed.starts_with("$argon2id$"));
+        assert!(verify("secret123", &hashed).await.unwrap());
+        assert!(!verify("secret124", &hashed).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_verify_plaintext() {
+        assert!(!is_hashed("secret123"));
+        assert!(verify("secret123", "secret123").await.unwrap());
+        assert!(!verify("secret124", "secret123").await.unwrap());
+    }
+}
@@ -0,0 +1,10 @@
+//! 随机值工具
+
+use rand::RngCore;
+
+/// 生成密码学安全的随机 token（十六进制），`bytes` 为随机字节数
+pub fn token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::rng().fill_bytes(&mut buf);
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -3,6 +3,7 @@ use bb8::Pool;
 use bb8_redis::RedisConnectionManager;
 use redis::{AsyncCommands, Script};
 use std::{
+    collections::HashMap,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -29,10 +30,7 @@ impl RedisUtil {
         value: T,
         expire: Duration,
     ) -> Result<(), Error> {
-        let expire: u64 = expire
-            .as_secs()
-            .try_into()
-            .map_err(|e| Error::Unknown(format!("类型转换失败:{}", e)))?;
+        let expire = expire.as_secs();
 
         let mut conn = self.pool.get().await?;
 
@@ -40,6 +38,51 @@ impl RedisUtil {
         Ok(())
     }
 
+    /// 仅当 key 已存在时覆盖值（带有效期），返回是否写入成功
+    pub async fn set_if_exists_with_expire<T: Send + Sync + redis::ToRedisArgs>(
+        &self,
+        key: &str,
+        value: T,
+        expire: Duration,
+    ) -> Result<bool, Error> {
+        let mut conn = self.pool.get().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("XX")
+            .arg("EX")
+            .arg(expire.as_secs())
+            .query_async(&mut *conn)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    /// 仅当 key 不存在时写入值（带有效期），返回是否写入成功
+    pub async fn set_if_absent_with_expire<T: Send + Sync + redis::ToRedisArgs>(
+        &self,
+        key: &str,
+        value: T,
+        expire: Duration,
+    ) -> Result<bool, Error> {
+        let mut conn = self.pool.get().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("EX")
+            .arg(expire.as_secs().max(1))
+            .query_async(&mut *conn)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    /// 仅重置已存在 key 的有效期，返回 key 是否存在
+    pub async fn expire(&self, key: &str, expire: Duration) -> Result<bool, Error> {
+        let mut conn = self.pool.get().await?;
+        let exists: bool = conn.expire(key, expire.as_secs() as i64).await?;
+        Ok(exists)
+    }
+
     /// 读取值
     pub async fn get<T: redis::FromRedisValue>(&self, key: &str) -> Result<Option<T>, Error> {
         let mut conn = self.pool.get().await?;
@@ -47,6 +90,13 @@ impl RedisUtil {
         Ok(result)
     }
 
+    /// 读取并删除值（一次性凭证使用，保证只能被消费一次）
+    pub async fn take<T: redis::FromRedisValue>(&self, key: &str) -> Result<Option<T>, Error> {
+        let mut conn = self.pool.get().await?;
+        let result: Option<T> = conn.get_del(key).await?;
+        Ok(result)
+    }
+
     /// 删除值
     pub async fn del(&self, key: &str) -> Result<(), Error> {
         let mut conn = self.pool.get().await?;
@@ -54,6 +104,109 @@ impl RedisUtil {
         Ok(())
     }
 
+    /// 自增计数，首次创建时设置有效期，返回自增后的值
+    ///
+    /// 在 MULTI 事务中先以 `SET NX EX` 创建带有效期的计数再自增，避免中途失败留下永不过期的计数
+    pub async fn incr_with_expire(&self, key: &str, expire: Duration) -> Result<i64, Error> {
+        let mut conn = self.pool.get().await?;
+        let (count,): (i64,) = redis::pipe()
+            .atomic()
+            .cmd("SET")
+            .arg(key)
+            .arg(0)
+            .arg("NX")
+            .arg("EX")
+            .arg(expire.as_secs().max(1))
+            .ignore()
+            .incr(key, 1)
+            .query_async(&mut *conn)
+            .await?;
+        Ok(count)
+    }
+
+    /// 自增计数并重置有效期（MULTI 事务），返回自增后的值
+    pub async fn incr_and_expire(&self, key: &str, expire: Duration) -> Result<i64, Error> {
+        let mut conn = self.pool.get().await?;
+        let (count,): (i64,) = redis::pipe()
+            .atomic()
+            .incr(key, 1)
+            .expire(key, expire.as_secs() as i64)
+            .ignore()
+            .query_async(&mut *conn)
+            .await?;
+        Ok(count)
+    }
+
+    /// 获取剩余有效期（秒），key 不存在或未设置有效期时返回 None
+    pub async fn ttl(&self, key: &str) -> Result<Option<u64>, Error> {
+        let mut conn = self.pool.get().await?;
+        let ttl: i64 = conn.ttl(key).await?;
+        Ok(u64::try_from(ttl).ok())
+    }
+
+    /// 批量读取值，结果与 keys 一一对应
+    pub async fn mget<T: redis::FromRedisValue>(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<Option<T>>, Error> {
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut conn = self.pool.get().await?;
+        let result: Vec<Option<T>> = redis::cmd("MGET").arg(keys).query_async(&mut *conn).await?;
+        Ok(result)
+    }
+
+    /// 批量删除值
+    pub async fn del_many(&self, keys: &[String]) -> Result<(), Error> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.pool.get().await?;
+        let _: () = conn.del(keys).await?;
+        Ok(())
+    }
+
+    /// 设置哈希字段
+    pub async fn hset<T: Send + Sync + redis::ToRedisArgs>(
+        &self,
+        key: &str,
+        field: &str,
+        value: T,
+    ) -> Result<(), Error> {
+        let mut conn = self.pool.get().await?;
+        let _: () = conn.hset(key, field, value).await?;
+        Ok(())
+    }
+
+    /// 读取哈希字段
+    pub async fn hget<T: redis::FromRedisValue>(
+        &self,
+        key: &str,
+        field: &str,
+    ) -> Result<Option<T>, Error> {
+        let mut conn = self.pool.get().await?;
+        let result: Option<T> = conn.hget(key, field).await?;
+        Ok(result)
+    }
+
+    /// 读取哈希全部字段
+    pub async fn hgetall(&self, key: &str) -> Result<HashMap<String, String>, Error> {
+        let mut conn = self.pool.get().await?;
+        let result: HashMap<String, String> = conn.hgetall(key).await?;
+        Ok(result)
+    }
+
+    /// 删除哈希字段
+    pub async fn hdel(&self, key: &str, fields: &[String]) -> Result<(), Error> {
+        if fields.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.pool.get().await?;
+        let _: () = conn.hdel(key, fields).await?;
+        Ok(())
+    }
+
     /// 获取可续期锁
     pub async fn acquire_lock(
         &self,
@@ -84,7 +237,7 @@ impl RedisLock {
         let ttl_ms: u64 = expire
             .as_millis()
             .try_into()
-            .map_err(|e| Error::Unknown(format!("类型转换失败:{}", e)))?;
+            .map_err(|e| Error::unknown("类型转换失败").with_source(e))?;
 
         let token = Uuid::new_v4().to_string();
         let mut conn = pool.get().await?;
@@ -149,7 +302,7 @@ impl RedisLock {
                             .await
                             .unwrap_or(0);
                     }
-                    sleep(Duration::from_millis(interval as u64)).await;
+                    sleep(Duration::from_millis(interval)).await;
                 }
             }
             .instrument(parent_span),
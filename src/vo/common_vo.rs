@@ -8,21 +8,24 @@
 //! 为各业务模块复用。
 
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationErrors};
+
+use crate::core::constant::MAX_PAGE_SIZE;
 
 // -----------------------------------------------------------------------------
 // 🔹 基础 ID 请求体
 // -----------------------------------------------------------------------------
 
 /// 单个 ID 请求体
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct IdReq {
     pub id: i64,
 }
 
 /// 批量 ID 请求体
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)]
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct IdsReq {
     pub ids: Vec<i64>,
@@ -48,6 +51,18 @@ pub enum LogicOp {
     Or,
 }
 
+/// 分页方式
+///
+/// - offset：按页码分页，返回总数
+/// - cursor：按排序键定位（keyset），通过 `nextCursor` 翻页，不做 COUNT，适合大表
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PageMode {
+    #[default]
+    Offset,
+    Cursor,
+}
+
 /// 比较运算符（=、>、like、in...）
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -73,13 +88,53 @@ pub enum CompareOp {
 ///
 /// 示例：
 /// ```json
-/// { "field": "age", "op": "gt", "values": ["18"] }
+/// { "field": "age", "op": "gt", "values": [18] }
 /// ```
-#[derive(Debug, Deserialize, Serialize, Clone)]
+///
+/// values 可为字符串、数字、布尔或 null，查询时按列类型转换
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
 pub struct Filter {
+    #[validate(length(min = 1, message = "过滤字段不能为空"))]
     pub field: String,
     pub op: CompareOp,
-    pub values: Vec<String>,
+    pub values: Vec<serde_json::Value>,
+}
+
+/// 过滤分组，children 按分组自身的 logic 组合，可继续嵌套分组
+///
+/// 示例（status = 1 OR sort > 10）：
+/// ```json
+/// {
+///   "logic": "or",
+///   "children": [
+///     { "field": "status", "op": "eq", "values": ["1"] },
+///     { "field": "sort", "op": "gt", "values": ["10"] }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+pub struct FilterGroup {
+    #[serde(default = "default_logic")]
+    pub logic: LogicOp,
+    #[validate(length(min = 1, message = "过滤分组不能为空"), nested)]
+    pub children: Vec<FilterNode>,
+}
+
+/// 过滤节点：单个条件或条件分组
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum FilterNode {
+    Filter(Filter),
+    Group(FilterGroup),
+}
+
+impl Validate for FilterNode {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            FilterNode::Filter(filter) => filter.validate(),
+            FilterNode::Group(group) => group.validate(),
+        }
+    }
 }
 
 /// 排序字段
@@ -88,8 +143,9 @@ pub struct Filter {
 /// ```json
 /// { "field": "created_at", "order": "desc" }
 /// ```
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Validate)]
 pub struct SortField {
+    #[validate(length(min = 1, message = "排序字段不能为空"))]
     pub field: String,
     pub order: Order,
 }
@@ -104,7 +160,11 @@ pub struct SortField {
 /// ```json
 /// {
 ///   "filters": [
-///     { "field": "name", "op": "like", "values": ["Alice"] }
+///     { "field": "name", "op": "like", "values": ["Alice"] },
+///     { "logic": "or", "children": [
+///       { "field": "status", "op": "eq", "values": ["1"] },
+///       { "field": "sort", "op": "gt", "values": ["10"] }
+///     ] }
 ///   ],
 ///   "logic": "and",
 ///   "page": 1,
@@ -114,17 +174,40 @@ pub struct SortField {
 ///   ]
 /// }
 /// ```
+///
+/// 列投影与关联展开：`"fields": ["id", "status"]`、`"expand": ["task", "details(id,contentType)"]`，
+/// 关联以其名称作为键返回，一对一为对象，一对多为数组（每行最多 50 条），括号内为关联的返回列
+///
+/// 游标分页：`"mode": "cursor"`，首页不传 cursor，之后传上一页返回的 `nextCursor`，
+/// 翻页期间过滤与排序须保持不变；`withTotal` 为 true 时额外返回总数
 #[derive(Debug, Deserialize, Serialize, Clone, Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct QueryReq {
-    pub filters: Option<Vec<Filter>>,
+    #[validate(nested)]
+    pub filters: Option<Vec<FilterNode>>,
     #[serde(default = "default_logic")]
     pub logic: LogicOp,
     #[serde(default = "default_page")]
     #[validate(range(min = 1, message = "页码必须大于等于 1"))]
     pub page: u64,
     #[serde(default = "default_size")]
+    #[validate(range(min = 1, max = MAX_PAGE_SIZE, message = "每页条数超出允许范围"))]
     pub size: u64,
+    #[validate(nested)]
     pub sorts: Option<Vec<SortField>>,
+    #[serde(default)]
+    pub mode: PageMode,
+    /// 游标分页时上一页返回的 nextCursor
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// 游标分页时是否统计总数
+    #[serde(default)]
+    pub with_total: bool,
+    /// 返回的列，未指定时返回全部可返回列
+    #[validate(length(min = 1, message = "返回字段不能为空"))]
+    pub fields: Option<Vec<String>>,
+    /// 展开的关联，如 report 的 task；可用括号指定关联的返回列
+    pub expand: Option<Vec<String>>,
 }
 
 fn default_logic() -> LogicOp {
@@ -152,11 +235,17 @@ fn default_size() -> u64 {
 ///   "data": [ { "id": 1, "name": "Alice" } ]
 /// }
 /// ```
+///
+/// 游标分页不返回 page，仅在请求 withTotal 时返回 total，还有下一页时返回 nextCursor
 #[derive(Debug, serde::Serialize)]
 pub struct PageResp<T> {
-    pub total: u64,
-    pub page: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
     pub page_size: u64,
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     pub data: Vec<T>,
 }
 
@@ -164,22 +253,26 @@ impl<T> PageResp<T> {
     /// 快速构造分页响应
     pub fn new(total: u64, page: u64, page_size: u64, data: Vec<T>) -> Self {
         Self {
-            total,
-            page,
+            total: Some(total),
+            page: Some(page),
             page_size,
+            next_cursor: None,
             data,
         }
     }
-    /// 将 PageResp<T> 映射成 PageResp<U>
-    pub fn map<U, F>(self, f: F) -> PageResp<U>
-    where
-        F: FnMut(T) -> U,
-    {
-        PageResp {
-            total: self.total,
-            page: self.page,
-            page_size: self.page_size,
-            data: self.data.into_iter().map(f).collect(),
+    /// 构造游标分页响应
+    pub fn cursor(
+        page_size: u64,
+        data: Vec<T>,
+        next_cursor: Option<String>,
+        total: Option<u64>,
+    ) -> Self {
+        Self {
+            total,
+            page: None,
+            page_size,
+            next_cursor,
+            data,
         }
     }
 }
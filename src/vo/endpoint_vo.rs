@@ -4,24 +4,36 @@ use validator::Validate;
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct InsertReq {
-    pub name: String,                //  名称
-    pub code: String,                //  接口唯一code
-    pub method: String,              //  HTTP 请求方法:get,post
-    pub domain_code: String,         //  域名code
-    pub path: String,                //  接口路径
+    #[validate(length(min = 1, max = 255, message = "名称长度必须在1到255个字符之间"))]
+    pub name: String, //  名称
+    #[validate(length(min = 1, max = 255, message = "code长度必须在1到255个字符之间"))]
+    pub code: String, //  接口唯一code
+    #[validate(length(min = 1, max = 32, message = "请求方法长度必须在1到32个字符之间"))]
+    pub method: String, //  HTTP 请求方法:get,post
+    #[validate(length(min = 1, max = 32, message = "域名code长度必须在1到32个字符之间"))]
+    pub domain_code: String, //  域名code
+    #[validate(length(min = 1, max = 255, message = "接口路径长度必须在1到255个字符之间"))]
+    pub path: String, //  接口路径
     pub description: Option<String>, //  接口描述
-    pub is_active: Option<i8>,       //  是否启用
+    #[validate(range(min = 0, max = 1, message = "是否启用只能为0或1"))]
+    pub is_active: Option<i8>, //  是否启用
 }
 
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateReq {
-    pub id: i64,                     //
-    pub name: Option<String>,        //  名称
-    pub code: Option<String>,        //  接口唯一code
-    pub method: Option<String>,      //  HTTP 请求方法:get,post
+    pub id: i64, //
+    #[validate(length(min = 1, max = 255, message = "名称长度必须在1到255个字符之间"))]
+    pub name: Option<String>, //  名称
+    #[validate(length(min = 1, max = 255, message = "code长度必须在1到255个字符之间"))]
+    pub code: Option<String>, //  接口唯一code
+    #[validate(length(min = 1, max = 32, message = "请求方法长度必须在1到32个字符之间"))]
+    pub method: Option<String>, //  HTTP 请求方法:get,post
+    #[validate(length(min = 1, max = 32, message = "域名code长度必须在1到32个字符之间"))]
     pub domain_code: Option<String>, //  域名code
-    pub path: Option<String>,        //  接口路径
+    #[validate(length(min = 1, max = 255, message = "接口路径长度必须在1到255个字符之间"))]
+    pub path: Option<String>, //  接口路径
     pub description: Option<String>, //  接口描述
-    pub is_active: Option<i8>,       //  是否启用
+    #[validate(range(min = 0, max = 1, message = "是否启用只能为0或1"))]
+    pub is_active: Option<i8>, //  是否启用
 }
@@ -4,13 +4,18 @@ use validator::Validate;
 #[derive(Deserialize, Debug, Validate)]
 #[serde(rename_all = "camelCase")]
 pub struct InsertReq {
+    #[validate(range(min = 0, message = "父菜单ID不能为负数"))]
     pub parent_id: i64,
+    #[validate(length(min = 1, max = 255, message = "路由路径长度必须在1到255个字符之间"))]
     pub path: String,
+    #[validate(length(min = 1, max = 64, message = "菜单名称长度必须在1到64个字符之间"))]
     pub name: String,
+    #[validate(length(max = 255, message = "组件路径不能超过255个字符"))]
     pub component: String,
     pub redirect: Option<String>,
     pub sort: i32,
     pub meta: Option<serde_json::Value>,
+    #[validate(range(min = 0, max = 1, message = "状态只能为0或1"))]
     pub status: i8,
     pub remark: Option<String>,
 }
@@ -19,13 +24,18 @@ pub struct InsertReq {
 #[serde(rename_all = "camelCase")]
 pub struct UpdateReq {
     pub id: i64,
+    #[validate(range(min = 0, message = "父菜单ID不能为负数"))]
     pub parent_id: Option<i64>,
+    #[validate(length(min = 1, max = 255, message = "路由路径长度必须在1到255个字符之间"))]
     pub path: Option<String>,
+    #[validate(length(min = 1, max = 64, message = "菜单名称长度必须在1到64个字符之间"))]
     pub name: Option<String>,
+    #[validate(length(max = 255, message = "组件路径不能超过255个字符"))]
     pub component: Option<String>,
     pub redirect: Option<String>,
     pub sort: Option<i32>,
     pub meta: Option<serde_json::Value>,
+    #[validate(range(min = 0, max = 1, message = "状态只能为0或1"))]
     pub status: Option<i8>,
     pub remark: Option<String>,
 }
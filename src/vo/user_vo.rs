@@ -1,32 +1,118 @@
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::{core::i18n::Locale, service::menu_service::MenuNode};
 
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
-
 pub struct LoginReq {
     #[validate(length(min = 3, max = 20, message = "用户名长度必须在3到20个字符之间"))]
     pub username: String,
-    #[validate(length(min = 3, max = 20, message = "密码长度必须在3到20个字符之间"))]
+    #[validate(length(min = 1, max = 128, message = "密码长度必须在1到128个字符之间"))]
     pub password: String,
+    /// 失败次数达到阈值后必填，由 `/user/captcha` 获取
+    pub captcha_id: Option<String>,
+    pub captcha_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptchaResp {
+    pub captcha_id: String,
+    /// PNG 图片，data URL 格式
+    pub image: String,
+    /// 有效期 秒
+    pub expires_in: u64,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginResp {
     pub user_id: i64,
-    pub token: String,
+    /// 需要两步验证时不返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// 仅 JWT 模式返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
     pub username: String,
     pub role: i32,
+    /// 须先修改密码，修改前只能访问无需权限的接口
+    pub must_change_password: bool,
+    /// 需要两步验证时返回，凭待验证令牌完成验证后才签发 token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_factor: Option<TwoFactorResp>,
+    /// 模拟登录时为发起模拟的管理员账号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorResp {
+    pub pending_token: String,
+    /// 是否需要先绑定验证器
+    pub enroll: bool,
+    /// 待验证令牌有效期 秒
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpPendingReq {
+    #[validate(length(min = 1, message = "pendingToken不能为空"))]
+    pub pending_token: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpVerifyReq {
+    #[validate(length(min = 1, message = "pendingToken不能为空"))]
+    pub pending_token: String,
+    /// 6 位验证码或恢复码
+    #[validate(length(min = 6, max = 16, message = "验证码长度必须在6到16个字符之间"))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpCodeReq {
+    #[validate(length(min = 6, max = 16, message = "验证码长度必须在6到16个字符之间"))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpEnrollResp {
+    /// Base32 密钥，无法扫码时手动输入
+    pub secret: String,
+    /// otpauth:// 地址，用于生成二维码
+    pub otpauth_uri: String,
+    /// 恢复码，仅返回这一次
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshReq {
+    /// cookie 模式下可省略，从 cookie 读取
+    #[serde(default)]
+    #[validate(length(min = 1, message = "refreshToken不能为空"))]
+    pub refresh_token: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PermissionResp {
     pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+    pub menus: Vec<MenuNode>,
     pub name: String,
     pub avatar: String,
     pub introduction: String,
+    /// 模拟登录时为发起模拟的管理员账号，前端据此提示
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Validate)]
@@ -34,10 +120,8 @@ pub struct PermissionResp {
 pub struct InsertReq {
     #[validate(length(min = 3, max = 20, message = "用户名长度必须在3到20个字符之间"))]
     pub username: String,
-    #[validate(length(min = 3, max = 20, message = "密码长度必须在3到20个字符之间"))]
+    /// 长度与复杂度由密码策略校验
     pub password: String,
-    #[validate(length(min = 3, max = 20, message = "盐值必须在3到20个字符之间"))]
-    pub salt: Option<String>,
     #[validate(range(min = 1, max = 10, message = "角色须在1到10之间"))]
     pub role: i32,
 }
@@ -48,12 +132,10 @@ pub struct UpdateReq {
     pub id: i64,
     #[validate(length(min = 3, max = 20, message = "用户名长度必须在3到20个字符之间"))]
     pub username: Option<String>,
-    #[validate(length(min = 3, max = 20, message = "密码长度必须在3到20个字符之间"))]
-    pub password: Option<String>,
-    #[validate(length(min = 3, max = 20, message = "盐值必须在3到20个字符之间"))]
-    pub salt: Option<String>,
     #[validate(range(min = 1, max = 10, message = "角色须在1到10之间"))]
     pub role: Option<i32>,
+    #[validate(range(min = 0, max = 1, message = "启用标志须为0或1"))]
+    pub enable_flag: Option<i8>,
 }
 
 #[derive(Serialize, Debug, Validate)]
@@ -61,10 +143,133 @@ pub struct UpdateReq {
 pub struct SearchResp {
     pub id: i64,
     pub username: String,
-    pub password: String,
-    pub salt: Option<String>,
     pub role: i32,
     pub enable_flag: i8,
     pub created_at: Option<chrono::NaiveDateTime>,
     pub updated_at: Option<chrono::NaiveDateTime>,
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResp {
+    pub session_id: String,
+    pub issued_at: chrono::NaiveDateTime,
+    pub last_seen: chrono::NaiveDateTime,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// 是否为当前请求所用的会话
+    pub current: bool,
+    /// 管理员模拟登录产生的会话，为该管理员账号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRevokeReq {
+    #[validate(length(min = 1, message = "会话ID不能为空"))]
+    pub session_id: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockReq {
+    #[validate(length(min = 1, message = "用户名不能为空"))]
+    pub username: String,
+    /// 同时解除该 IP 的锁定
+    pub ip: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreateReq {
+    #[validate(length(min = 1, max = 64, message = "名称长度必须在1到64个字符之间"))]
+    pub name: String,
+    /// 授权范围（权限码）
+    #[validate(length(min = 1, message = "授权范围不能为空"))]
+    pub scopes: Vec<String>,
+    /// 有效天数，不传表示永不过期
+    #[validate(range(min = 1, max = 3650, message = "有效天数须在1到3650之间"))]
+    pub expire_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResp {
+    pub id: i64,
+    pub name: String,
+    /// key 前缀，用于识别
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    pub created_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreateResp {
+    /// 完整 key，仅在创建时返回一次
+    pub key: String,
+    #[serde(flatten)]
+    pub info: ApiKeyResp,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcLoginResp {
+    /// 身份提供方授权地址，前端跳转至此
+    pub authorize_url: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcCallbackReq {
+    #[validate(length(min = 1, message = "code不能为空"))]
+    pub code: String,
+    #[validate(length(min = 1, message = "state不能为空"))]
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferenceReq {
+    /// 语言（zh-CN / en-US），为空时按 Accept-Language 选择
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+}
+
+fn validate_locale(tag: &str) -> Result<(), ValidationError> {
+    if tag.is_empty() || Locale::parse(tag).is_some() {
+        return Ok(());
+    }
+    Err(ValidationError::new("locale").with_message("语言不受支持".into()))
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordChangeReq {
+    #[validate(length(min = 1, max = 128, message = "原密码长度必须在1到128个字符之间"))]
+    pub old_password: String,
+    /// 长度与复杂度由密码策略校验
+    pub new_password: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetResp {
+    /// 一次性重置令牌，由管理员转交用户
+    pub reset_token: String,
+    /// 有效期 秒
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetConfirmReq {
+    #[validate(length(min = 1, message = "resetToken不能为空"))]
+    pub reset_token: String,
+    /// 长度与复杂度由密码策略校验
+    pub new_password: String,
+}